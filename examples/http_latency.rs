@@ -0,0 +1,40 @@
+use std::time::{Duration, Instant};
+use waitforit::Wait;
+
+// Compares the latency of polling an HTTP endpoint with a fresh connection each
+// time against `Wait::new_http_get`, which reuses pooled connections.
+//
+//     cargo run --release --example http_latency -- https://example.com/
+//
+// Remote and TLS endpoints save the most, since each fresh connection costs at
+// least one extra round trip (plus the TLS handshake).
+fn main() {
+    let url = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "https://example.com/".to_string());
+    const POLLS: u32 = 20;
+
+    let start = Instant::now();
+    for _ in 0..POLLS {
+        // A new agent has an empty connection pool
+        let _ = ureq::Agent::new().get(&url).call();
+    }
+    let fresh = start.elapsed() / POLLS;
+
+    let wait = Wait::new_http_get(&url, 200);
+    wait.condition_met(); // Warm up the pool
+    let start = Instant::now();
+    for _ in 0..POLLS {
+        wait.condition_met();
+    }
+    let pooled = start.elapsed() / POLLS;
+
+    println!("fresh connection: {:>8.1?} per poll", fresh);
+    println!("pooled:           {:>8.1?} per poll", pooled);
+    if pooled > Duration::ZERO {
+        println!(
+            "speedup:          {:>8.1}x",
+            fresh.as_secs_f64() / pooled.as_secs_f64()
+        );
+    }
+}
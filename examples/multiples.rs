@@ -1,6 +1,7 @@
 use std::time::Duration;
 use waitforit::Wait;
 
+#[allow(clippy::disallowed_names)]
 fn main() {
     // Wait for foo.txt to exist
     let foo_exists = Wait::new_file_exists("foo.txt");
@@ -12,9 +12,8 @@ fn main() {
 
     // wait until ten seconds has passed and the lockfile is gone
     let w = ten_sec & lockfile;
-    let start = std::time::Instant::now();
-    w.wait(CHECK_DURATION);
-    println!("Step 1 complete after {:?}", start.elapsed());
+    let elapsed = w.wait_elapsed(CHECK_DURATION);
+    println!("Step 1 complete after {elapsed:?}");
 
     // w      is     (ten seconds has passed) and (not(lockfile exists))
     // not(w) is not((ten seconds has passed) and (not(lockfile exists)))
@@ -23,7 +22,6 @@ fn main() {
     let lockfile = !Wait::new_file_exists("something.lock");
     let w = ten_sec & lockfile;
     let not_w = !w;
-    let start = std::time::Instant::now();
-    not_w.wait(CHECK_DURATION);
-    println!("Step 2 complete after {:?}", start.elapsed());
+    let elapsed = not_w.wait_elapsed(CHECK_DURATION);
+    println!("Step 2 complete after {elapsed:?}");
 }
@@ -0,0 +1,651 @@
+//! Non-blocking evaluation of conditions on a tokio runtime, with the `async`
+//! feature.
+
+#[cfg(feature = "http")]
+use crate::http;
+#[cfg(feature = "tls")]
+use crate::tls;
+use crate::{poll, validate_host_port, Wait, Waits, MAX_BANNER_BYTES};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::AsyncReadExt,
+    net::{lookup_host, TcpStream, UdpSocket},
+};
+
+/// A boxed check, so that nested conditions can be checked recursively.
+type Check<'a> = Pin<Box<dyn Future<Output = bool> + Send + 'a>>;
+
+impl Wait {
+    /// Checks whether this condition is met, like [Self::condition_met], but
+    /// without blocking the async runtime's thread.
+    ///
+    /// Network conditions (TCP, UDP, DNS, and HTTP) and [Wait::Command] are
+    /// checked with tokio's non-blocking I/O. The rest only inspect local state
+    /// (eg, file metadata), so they're checked in place. Custom conditions are
+    /// called in place too, so they shouldn't block.
+    pub async fn condition_met_async(&self) -> bool {
+        self.check_async().await
+    }
+
+    fn check_async(&self) -> Check<'_> {
+        Box::pin(async move {
+            let met = match self {
+                Wait::TcpHost { not, host, timeout } => tcp_connects(host, *timeout).await ^ not,
+                Wait::TcpBanner {
+                    not,
+                    host,
+                    expect,
+                    read_timeout,
+                } => {
+                    tcp_banner(host, *read_timeout)
+                        .await
+                        .is_some_and(|banner| banner.starts_with(expect.as_bytes()))
+                        ^ not
+                }
+                #[cfg(feature = "tls")]
+                Wait::TlsCertValid {
+                    not,
+                    host,
+                    min_remaining,
+                    timeout,
+                } => {
+                    // rustls's handshake blocks, so it's done on tokio's blocking pool
+                    let (host, timeout) = (host.clone(), *timeout);
+                    let remaining =
+                        tokio::task::spawn_blocking(move || tls::cert_remaining(&host, timeout));
+                    match remaining.await {
+                        Ok(Some(remaining)) => (remaining > *min_remaining) ^ not,
+                        _ => false,
+                    }
+                }
+                Wait::DnsResolves { not, host } => dns_resolves(host).await ^ not,
+                Wait::UdpHost {
+                    not,
+                    host,
+                    payload,
+                    expect_reply,
+                    timeout,
+                } => {
+                    udp_reachable(host, payload, *expect_reply, *timeout)
+                        .await
+                        .is_ok()
+                        ^ not
+                }
+                Wait::Command {
+                    not,
+                    program,
+                    args,
+                    expected_code,
+                } => match exit_code(program, args).await {
+                    Some(code) => (code == *expected_code) ^ not,
+                    None => false,
+                },
+                #[cfg(feature = "http")]
+                Wait::HttpGet {
+                    not,
+                    url,
+                    status,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    match http::status_async(request).await {
+                        Some(s) => (*status == s) ^ not,
+                        None => *not,
+                    }
+                }
+                #[cfg(feature = "http")]
+                Wait::HttpGetRange {
+                    not,
+                    url,
+                    statuses,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    match http::status_async(request).await {
+                        Some(s) => statuses.contains(&s) ^ not,
+                        None => *not,
+                    }
+                }
+                #[cfg(feature = "http")]
+                Wait::HttpPost {
+                    not,
+                    url,
+                    body,
+                    content_type,
+                    status,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::POST, url, options)
+                        .header("Content-Type", content_type.as_str())
+                        .body(body.clone());
+                    match http::status_async(request).await {
+                        Some(s) => (*status == s) ^ not,
+                        None => *not,
+                    }
+                }
+                #[cfg(feature = "http")]
+                Wait::HttpBody {
+                    not,
+                    url,
+                    contains,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    match http::body_async(request).await {
+                        Some(body) => body.contains(contains.as_str()) ^ not,
+                        None => *not,
+                    }
+                }
+                #[cfg(feature = "http")]
+                Wait::HttpHeader {
+                    not,
+                    url,
+                    header,
+                    equals,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    match http::header_async(request, header).await {
+                        Some(value) => {
+                            http::header_matches(value.as_deref(), equals.as_deref()) ^ not
+                        }
+                        None => *not,
+                    }
+                }
+                #[cfg(feature = "http")]
+                Wait::HttpMulti {
+                    not,
+                    urls,
+                    status,
+                    mode,
+                    options,
+                } => http::multi_status_async(urls, *status, *mode, options).await ^ not,
+                #[cfg(feature = "http")]
+                Wait::HttpHead {
+                    not,
+                    url,
+                    status,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::HEAD, url, options);
+                    match http::status_async(request).await {
+                        Some(s) => (*status == s) ^ not,
+                        None => *not,
+                    }
+                }
+                #[cfg(feature = "http")]
+                Wait::HttpLatency {
+                    not,
+                    url,
+                    max,
+                    options,
+                } => {
+                    let start = Instant::now();
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    let status = http::status_async(request).await;
+                    http::within_latency(status, start.elapsed(), *max) ^ not
+                }
+                #[cfg(feature = "json")]
+                Wait::HttpJson {
+                    not,
+                    url,
+                    pointer,
+                    equals,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    match http::body_async(request).await {
+                        Some(body) => http::json_matches(&body, pointer, equals, *not),
+                        None => *not,
+                    }
+                }
+                #[cfg(all(feature = "http", feature = "regex"))]
+                Wait::HttpBodyRegex {
+                    not,
+                    url,
+                    pattern,
+                    options,
+                } => {
+                    let request = http::request_async(reqwest::Method::GET, url, options);
+                    match http::body_async(request).await {
+                        Some(body) => pattern.is_match(&body) ^ not,
+                        None => *not,
+                    }
+                }
+                Wait::Throttled {
+                    not,
+                    inner,
+                    interval,
+                    last_check,
+                    last_met,
+                } => {
+                    // Claim this interval's check under the lock, so that only one
+                    // of several concurrent checks polls `inner`
+                    let claimed = last_check.update(|last_check| {
+                        let due = last_check.is_none_or(|last| last.elapsed() >= *interval);
+                        if due {
+                            *last_check = Some(Instant::now());
+                        }
+                        due
+                    });
+                    if claimed {
+                        last_met.set(inner.check_async().await);
+                    }
+                    last_met.get() ^ not
+                }
+                _ => self.check(),
+            };
+            #[cfg(feature = "log")]
+            log::debug!("{self}: {}", if met { "met" } else { "not met" });
+            #[cfg(feature = "tracing")]
+            tracing::trace!(condition = %self, met);
+            met
+        })
+    }
+
+    /// Wait for the completion of this condition without blocking the async
+    /// runtime's thread, checking every `interval` with
+    /// [condition_met_async](Self::condition_met_async).
+    pub async fn wait_async(&self, interval: Duration) {
+        #[cfg(feature = "log")]
+        let start = Instant::now();
+        let wait = poll::wait_async(|| self.condition_met_async(), interval);
+        #[cfg(feature = "tracing")]
+        let wait = tracing::Instrument::instrument(
+            wait,
+            tracing::info_span!("wait", condition = %self, interval = ?interval),
+        );
+        wait.await;
+        #[cfg(feature = "log")]
+        log::info!("{self} met after {:?}", start.elapsed());
+    }
+}
+
+impl Waits {
+    /// Checks whether this condition is met, like [Self::condition_met], but
+    /// without blocking the async runtime's thread (see
+    /// [Wait::condition_met_async]).
+    ///
+    /// Both sides of an `Or` or `And` are checked concurrently, and as soon as
+    /// one side decides the result (eg, an `Or` side is met), the other side's
+    /// check is abandoned. Both sides of an `Xor` are always checked
+    /// concurrently. The conditions of an `NOf` are checked one at a time,
+    /// stopping once the result is decided, as [Self::condition_met] does.
+    pub async fn condition_met_async(&self) -> bool {
+        self.check_async().await
+    }
+
+    fn check_async(&self) -> Check<'_> {
+        Box::pin(async move {
+            match self {
+                Waits::Single(w) => w.check_async().await,
+                Waits::Or(cc) => {
+                    let (mut left, mut right) = (cc.0.check_async(), cc.1.check_async());
+                    tokio::select! {
+                        met = &mut left => met || right.await,
+                        met = &mut right => met || left.await,
+                    }
+                }
+                Waits::And(cc) => {
+                    let (mut left, mut right) = (cc.0.check_async(), cc.1.check_async());
+                    tokio::select! {
+                        met = &mut left => met && right.await,
+                        met = &mut right => met && left.await,
+                    }
+                }
+                Waits::Xor(cc) => {
+                    let (left, right) = tokio::join!(cc.0.check_async(), cc.1.check_async());
+                    left ^ right
+                }
+                Waits::NOf { n, conditions } => {
+                    let mut met = 0;
+                    for (i, c) in conditions.iter().enumerate() {
+                        if met >= *n || met + (conditions.len() - i) < *n {
+                            break;
+                        }
+                        if c.check_async().await {
+                            met += 1;
+                        }
+                    }
+                    met >= *n
+                }
+                Waits::Stable {
+                    inner,
+                    consecutive,
+                    count,
+                } => crate::waits::stable(inner.check_async().await, *consecutive, count),
+                Waits::SettledFor {
+                    inner,
+                    duration,
+                    since,
+                } => crate::waits::settled(inner.check_async().await, *duration, since),
+            }
+        })
+    }
+
+    /// Wait for the completion of this condition without blocking the async
+    /// runtime's thread, checking every `interval` with
+    /// [condition_met_async](Self::condition_met_async).
+    pub async fn wait_async(&self, interval: Duration) {
+        #[cfg(feature = "log")]
+        let start = Instant::now();
+        let wait = poll::wait_async(|| self.condition_met_async(), interval);
+        #[cfg(feature = "tracing")]
+        let wait = tracing::Instrument::instrument(
+            wait,
+            tracing::info_span!("wait", condition = %self, interval = ?interval),
+        );
+        wait.await;
+        #[cfg(feature = "log")]
+        log::info!("{self} met after {:?}", start.elapsed());
+    }
+}
+
+/// The async counterpart to `tcp_connects` in the `wait` module.
+async fn tcp_connects(host: &str, timeout: Duration) -> bool {
+    let Ok(addrs) = lookup_host(host).await else {
+        return false;
+    };
+
+    for addr in addrs {
+        if let Ok(Ok(_)) = tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+            return true;
+        }
+    }
+    false
+}
+
+/// The async counterpart to `tcp_banner` in the `wait` module.
+async fn tcp_banner(host: &str, timeout: Duration) -> Option<Vec<u8>> {
+    let mut stream = None;
+    for addr in lookup_host(host).await.ok()? {
+        if let Ok(Ok(s)) = tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+            stream = Some(s);
+            break;
+        }
+    }
+    let mut stream = stream?;
+
+    let mut banner = Vec::new();
+    let read = async {
+        let mut buf = [0; MAX_BANNER_BYTES];
+        while banner.len() < MAX_BANNER_BYTES && !banner.contains(&b'\n') {
+            match stream
+                .read(&mut buf[..MAX_BANNER_BYTES - banner.len()])
+                .await
+            {
+                Ok(0) | Err(_) => break,
+                Ok(n) => banner.extend_from_slice(&buf[..n]),
+            }
+        }
+    };
+    // Whatever was read by the deadline is the banner
+    let _ = tokio::time::timeout(timeout, read).await;
+
+    Some(banner)
+}
+
+/// The async counterpart to `dns_resolves` in the `wait` module. A failed lookup
+/// doesn't resolve.
+async fn dns_resolves(host: &str) -> bool {
+    if validate_host_port(host) {
+        lookup_host(host)
+            .await
+            .is_ok_and(|mut addrs| addrs.next().is_some())
+    } else {
+        lookup_host((host, 0))
+            .await
+            .is_ok_and(|mut addrs| addrs.next().is_some())
+    }
+}
+
+/// The async counterpart to `udp_reachable` in the `wait` module.
+async fn udp_reachable(
+    host: &str,
+    payload: &[u8],
+    expect_reply: bool,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    let mut result = Err(std::io::ErrorKind::NotFound.into());
+    for addr in lookup_host(host).await? {
+        result = udp_reachable_at(addr, payload, expect_reply, timeout).await;
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+/// The async counterpart to `udp_reachable_at` in the `wait` module.
+async fn udp_reachable_at(
+    addr: SocketAddr,
+    payload: &[u8],
+    expect_reply: bool,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    let local = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(addr).await?;
+    socket.send(payload).await?;
+
+    if expect_reply {
+        tokio::time::timeout(timeout, socket.recv(&mut [0; 65536]))
+            .await
+            .map_err(|_| std::io::ErrorKind::TimedOut)??;
+    }
+
+    Ok(())
+}
+
+/// The async counterpart to `process::exit_code`.
+async fn exit_code(program: &str, args: &[String]) -> Option<i32> {
+    tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .ok()?
+        .code()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Wait, Waits};
+    use std::time::{Duration, Instant};
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn throttled_concurrent() {
+        use crate::testing::{http_response, serve_http_recorded};
+
+        let (url, requests) = serve_http_recorded(http_response("200 OK", &[], ""));
+        let w = Wait::new_throttled(Wait::new_http_get(url, 200), Duration::from_secs(60));
+
+        // However many check at once, only one of them polls the inner condition
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .unwrap();
+                    runtime.block_on(w.condition_met_async());
+                });
+            }
+        });
+        assert_eq!(requests.try_iter().count(), 1);
+        assert!(w.condition_met());
+    }
+
+    #[tokio::test]
+    async fn tcp_connect() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        assert!(Wait::new_tcp_connect(&addr).condition_met_async().await);
+
+        drop(listener);
+        assert!(!Wait::new_tcp_connect(&addr).condition_met_async().await);
+        assert!((!Wait::new_tcp_connect(&addr)).condition_met_async().await);
+    }
+
+    #[tokio::test]
+    async fn tcp_banner() {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let _ = stream.unwrap().write_all(b"220 smtp.example.com ESMTP\r\n");
+            }
+        });
+
+        assert!(
+            Wait::new_tcp_banner(&host, "220")
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            !Wait::new_tcp_banner(&host, "SSH")
+                .condition_met_async()
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn dns_resolves() {
+        assert!(
+            Wait::new_dns_resolves("localhost")
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            !Wait::new_dns_resolves("nonexistent.invalid")
+                .condition_met_async()
+                .await
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command() {
+        assert!(
+            Wait::new_command("true", Vec::<String>::new(), 0)
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            !Wait::new_command("false", Vec::<String>::new(), 0)
+                .condition_met_async()
+                .await
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn http() {
+        use crate::testing::{http_response, serve_http};
+
+        let url = serve_http(http_response("503 Service Unavailable", &[], "starting"));
+        assert!(Wait::new_http_get(&url, 503).condition_met_async().await);
+        assert!(!Wait::new_http_get(&url, 200).condition_met_async().await);
+        assert!(
+            Wait::new_http_body(&url, "start")
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            Wait::new_http_post(&url, "{}", "application/json", 503)
+                .condition_met_async()
+                .await
+        );
+
+        let target = serve_http(http_response("200 OK", &[], ""));
+        assert!(
+            Wait::new_http_multi([&target, &target], 200, crate::MultiMode::All)
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            !Wait::new_http_multi([&target, &url], 200, crate::MultiMode::All)
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            Wait::new_http_multi([&url, &target], 200, crate::MultiMode::Any)
+                .condition_met_async()
+                .await
+        );
+        let redirect = serve_http(http_response("302 Found", &[("Location", &target)], ""));
+        assert!(
+            Wait::new_http_get(&redirect, 200)
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            Wait::new_http_get(&redirect, 302)
+                .with_follow_redirects(false)
+                .condition_met_async()
+                .await
+        );
+
+        let url = crate::testing::serve_http_basic_auth("monitor", "hunter2");
+        assert!(
+            Wait::new_http_get(&url, 200)
+                .with_basic_auth("monitor", "hunter2")
+                .condition_met_async()
+                .await
+        );
+        assert!(Wait::new_http_get(&url, 401).condition_met_async().await);
+
+        let url = serve_http(http_response("200 OK", &[("X-Ready", "true")], ""));
+        assert!(
+            Wait::new_http_header_equals(&url, "x-ready", "true")
+                .condition_met_async()
+                .await
+        );
+        assert!(
+            !Wait::new_http_header(&url, "X-Started")
+                .condition_met_async()
+                .await
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn or_short_circuits() {
+        use crate::testing::serve_nothing;
+
+        // The HTTP check would take the full timeout, but the other side is met
+        let hung =
+            Wait::new_http_get(serve_nothing(), 200).with_http_timeout(Duration::from_secs(10));
+        let w = hung | Wait::new_custom_closure(|| true);
+
+        let start = Instant::now();
+        assert!(w.condition_met_async().await);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn wait_async() {
+        let w: Waits = Wait::new_elapsed_from_duration(Duration::from_millis(20))
+            ^ Wait::new_custom_closure(|| false);
+
+        let start = Instant::now();
+        w.wait_async(Duration::from_millis(5)).await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::Wait;
+use std::time::Duration;
+
+/// The default value of [WaitBuilder::interval].
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configures a [Wait] along with how it's waited for, as an alternative to
+/// passing the interval and timeout to each `wait*` call.
+///
+/// ```
+/// use std::time::Duration;
+/// use waitforit::Wait;
+///
+/// let unlocked = Wait::new_file_exists("/tmp/waitforit-doc.lock")
+///     .builder()
+///     .not()
+///     .interval(Duration::from_millis(100))
+///     .timeout(Duration::from_secs(10))
+///     .build();
+///
+/// assert!(unlocked.wait());
+/// ```
+#[derive(Clone, Debug)]
+pub struct WaitBuilder {
+    wait: Wait,
+    not: bool,
+    interval: Duration,
+    timeout: Option<Duration>,
+}
+
+impl WaitBuilder {
+    /// Starts configuring `wait`, which is checked every [DEFAULT_POLL_INTERVAL]
+    /// with no timeout unless told otherwise.
+    pub fn new(wait: Wait) -> Self {
+        Self {
+            wait,
+            not: false,
+            interval: DEFAULT_POLL_INTERVAL,
+            timeout: None,
+        }
+    }
+
+    /// Negates the condition, as with `!`. Negating it twice undoes this.
+    // Chained as a method, so that `std::ops::Not` needn't be in scope
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(mut self) -> Self {
+        self.not = !self.not;
+        self
+    }
+
+    /// Sets how often the condition is checked.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how long [ConfiguredWait::wait] waits before giving up.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Finishes configuring the condition.
+    pub fn build(self) -> ConfiguredWait {
+        ConfiguredWait {
+            wait: if self.not { !self.wait } else { self.wait },
+            interval: self.interval,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl From<Wait> for WaitBuilder {
+    fn from(wait: Wait) -> Self {
+        Self::new(wait)
+    }
+}
+
+/// A [Wait] with the interval and timeout to wait for it with, from a
+/// [WaitBuilder].
+#[derive(Clone, Debug)]
+pub struct ConfiguredWait {
+    wait: Wait,
+    interval: Duration,
+    timeout: Option<Duration>,
+}
+
+impl ConfiguredWait {
+    /// The condition, already negated if [WaitBuilder::not] was used.
+    pub fn condition(&self) -> &Wait {
+        &self.wait
+    }
+
+    /// How often the condition is checked.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// How long to wait before giving up, if ever.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Checks the condition once. See [Wait::condition_met].
+    pub fn condition_met(&self) -> bool {
+        self.wait.condition_met()
+    }
+
+    /// Waits for the condition, checking it every interval. This will block the
+    /// thread.
+    ///
+    /// Returns `true` if the condition was met, or `false` if the timeout (if
+    /// any) elapsed first. See [Wait::wait_timeout].
+    pub fn wait(&self) -> bool {
+        match self.timeout {
+            Some(timeout) => self.wait.wait_timeout(self.interval, timeout),
+            None => {
+                self.wait.wait(self.interval);
+                true
+            }
+        }
+    }
+}
+
+impl Wait {
+    /// Starts configuring this condition with a [WaitBuilder].
+    pub fn builder(self) -> WaitBuilder {
+        WaitBuilder::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn build() {
+        use super::{WaitBuilder, DEFAULT_POLL_INTERVAL};
+        use crate::Wait;
+        use std::time::Duration;
+
+        let w = WaitBuilder::new(Wait::new_always()).build();
+        assert_eq!(w.interval(), DEFAULT_POLL_INTERVAL);
+        assert_eq!(w.timeout(), None);
+        assert!(w.condition_met());
+        assert!(w.wait());
+
+        let w = Wait::new_always()
+            .builder()
+            .not()
+            .interval(Duration::from_millis(10))
+            .timeout(Duration::from_millis(50))
+            .build();
+        assert!(*w.condition() == !Wait::new_always());
+        assert_eq!(w.interval(), Duration::from_millis(10));
+        assert!(!w.condition_met());
+        assert!(!w.wait());
+
+        let w = Wait::new_always().builder().not().not().build();
+        assert!(*w.condition() == Wait::new_always());
+    }
+}
@@ -0,0 +1,466 @@
+use std::{io::Read, ops::RangeInclusive, sync::OnceLock, time::Duration};
+
+/// The default value of [HttpOptions::timeout].
+pub const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The most of an HTTP response body that will be read when checking its contents.
+/// Anything beyond this is ignored so that a huge or streaming response can't
+/// exhaust memory.
+pub const MAX_HTTP_BODY_BYTES: u64 = 1024 * 1024;
+
+/// A class of HTTP status codes, as grouped by their first digit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusClass {
+    /// 1xx
+    Informational,
+    /// 2xx
+    Success,
+    /// 3xx
+    Redirection,
+    /// 4xx
+    ClientError,
+    /// 5xx
+    ServerError,
+}
+
+impl StatusClass {
+    /// The status codes in this class.
+    pub fn range(self) -> RangeInclusive<u16> {
+        match self {
+            StatusClass::Informational => 100..=199,
+            StatusClass::Success => 200..=299,
+            StatusClass::Redirection => 300..=399,
+            StatusClass::ClientError => 400..=499,
+            StatusClass::ServerError => 500..=599,
+        }
+    }
+}
+
+/// How the responses from the URLs of a [Wait::HttpMulti](crate::Wait::HttpMulti)
+/// are combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MultiMode {
+    /// At least one URL must return the status.
+    Any,
+    /// Every URL must return the status.
+    All,
+}
+
+impl std::fmt::Display for MultiMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultiMode::Any => f.write_str("any"),
+            MultiMode::All => f.write_str("all"),
+        }
+    }
+}
+
+/// Options for the requests made by the HTTP variants of [Wait](crate::Wait).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct HttpOptions {
+    /// The longest a single request may take, including connecting and reading
+    /// the response. Defaults to [DEFAULT_HTTP_TIMEOUT].
+    pub timeout: Duration,
+    /// Whether to follow up to [MAX_HTTP_REDIRECTS] redirects, checking the final
+    /// response. When false, a redirect is itself the response, so its status
+    /// (eg, 302) is what's checked. Defaults to true.
+    pub follow_redirects: bool,
+    /// Credentials to send with HTTP basic authentication. Defaults to none.
+    pub auth: Option<BasicAuth>,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_HTTP_TIMEOUT,
+            follow_redirects: true,
+            auth: None,
+        }
+    }
+}
+
+/// Credentials for HTTP basic authentication, sent in an `Authorization` header.
+///
+/// The password is redacted from this type's `Debug` output (and so from
+/// [HttpOptions]'s and [Wait](crate::Wait)'s), and a `Wait`'s `Display` doesn't
+/// include any of its [HttpOptions]. With the `serde` feature, though, both are
+/// serialized in the clear so that they can be loaded from a config file.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasicAuth {
+    /// The user to authenticate as.
+    pub username: String,
+    /// That user's password.
+    pub password: String,
+}
+
+impl BasicAuth {
+    /// The value of the `Authorization` header for these credentials.
+    fn header(&self) -> String {
+        let credentials = format!("{}:{}", self.username, self.password);
+        format!("Basic {}", base64(credentials.as_bytes()))
+    }
+}
+
+impl std::fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Encodes `bytes` as standard, padded base64.
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, b[0], b[1], b[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// The most redirects followed by a request, when [HttpOptions::follow_redirects]
+/// is set.
+pub const MAX_HTTP_REDIRECTS: u32 = 5;
+
+/// The agents shared by all HTTP requests, so that connections are pooled and kept
+/// alive between checks rather than paying for TCP and TLS handshakes each time.
+/// Redirects are configured per agent, so there's one that follows them and one
+/// that doesn't.
+fn agent(follow_redirects: bool) -> &'static ureq::Agent {
+    static FOLLOWING: OnceLock<ureq::Agent> = OnceLock::new();
+    static NOT_FOLLOWING: OnceLock<ureq::Agent> = OnceLock::new();
+
+    let (agent, redirects) = if follow_redirects {
+        (&FOLLOWING, MAX_HTTP_REDIRECTS)
+    } else {
+        (&NOT_FOLLOWING, 0)
+    };
+    agent.get_or_init(|| ureq::AgentBuilder::new().redirects(redirects).build())
+}
+
+/// Creates a request configured according to `options`.
+pub(crate) fn request(method: &str, url: &str, options: &HttpOptions) -> ureq::Request {
+    let request = agent(options.follow_redirects)
+        .request(method, url)
+        .timeout(options.timeout);
+    match &options.auth {
+        Some(auth) => request.set("Authorization", &auth.header()),
+        None => request,
+    }
+}
+
+/// Gets the status code from the result of an HTTP request, or `None` if no
+/// response could be received (eg, the connection was refused).
+pub(crate) fn status(result: Result<ureq::Response, ureq::Error>) -> Option<u16> {
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(ureq::Error::Transport(_)) => return None,
+    };
+
+    let status = response.status();
+    drain(response);
+    Some(status)
+}
+
+/// Whether GETs to `urls` return `status`, from any or all of them according to
+/// `mode`. With `Any`, they're requested in order until one does. With `All`,
+/// they're requested concurrently on scoped threads.
+pub(crate) fn multi_status(
+    urls: &[String],
+    status: u16,
+    mode: MultiMode,
+    options: &HttpOptions,
+) -> bool {
+    let returns_status =
+        |url: &String| self::status(request("GET", url, options).call()) == Some(status);
+    match mode {
+        MultiMode::Any => urls.iter().any(returns_status),
+        MultiMode::All => std::thread::scope(|scope| {
+            let checks = urls
+                .iter()
+                .map(|url| scope.spawn(move || returns_status(url)))
+                .collect::<Vec<_>>();
+            checks
+                .into_iter()
+                .all(|check| check.join().unwrap_or(false))
+        }),
+    }
+}
+
+/// Gets the value of the header `name` (case-insensitively) from the result of
+/// an HTTP request, regardless of its status code. Returns `None` if no response
+/// could be received, or `Some(None)` if it doesn't have that header.
+pub(crate) fn header(
+    result: Result<ureq::Response, ureq::Error>,
+    name: &str,
+) -> Option<Option<String>> {
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(ureq::Error::Transport(_)) => return None,
+    };
+
+    let value = response.header(name).map(str::to_owned);
+    drain(response);
+    Some(value)
+}
+
+/// Whether a response's header `value` (`None` if it's missing) meets a
+/// [Wait::HttpHeader](crate::Wait::HttpHeader): present, and equal to `equals`
+/// if given.
+pub(crate) fn header_matches(value: Option<&str>, equals: Option<&str>) -> bool {
+    match (value, equals) {
+        (Some(value), Some(equals)) => value == equals,
+        (value, None) => value.is_some(),
+        (None, Some(_)) => false,
+    }
+}
+
+/// Whether a response with `status` (`None` if there wasn't one) that took
+/// `elapsed` meets a [Wait::HttpLatency](crate::Wait::HttpLatency): successful,
+/// and faster than `max`.
+pub(crate) fn within_latency(status: Option<u16>, elapsed: Duration, max: Duration) -> bool {
+    status.is_some_and(|s| StatusClass::Success.range().contains(&s)) && elapsed < max
+}
+
+/// Whether a response's `body` meets a [Wait::HttpJson](crate::Wait::HttpJson)
+/// negated according to `not`. A body that isn't JSON doesn't meet it either way.
+#[cfg(feature = "json")]
+pub(crate) fn json_matches(
+    body: &str,
+    pointer: &str,
+    equals: &serde_json::Value,
+    not: bool,
+) -> bool {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(json) => (json.pointer(pointer) == Some(equals)) ^ not,
+        Err(_) => false,
+    }
+}
+
+/// Reads and discards (up to [MAX_HTTP_BODY_BYTES] of) `response`'s body. The
+/// connection only goes back to the agent's pool once the body is read.
+fn drain(response: ureq::Response) {
+    let _ = std::io::copy(
+        &mut response.into_reader().take(MAX_HTTP_BODY_BYTES),
+        &mut std::io::sink(),
+    );
+}
+
+/// Reads (up to [MAX_HTTP_BODY_BYTES] of) the body from the result of an HTTP
+/// request, regardless of its status code. Returns `None` if no response could
+/// be received.
+pub(crate) fn body(result: Result<ureq::Response, ureq::Error>) -> Option<String> {
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(ureq::Error::Transport(_)) => return None,
+    };
+
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_HTTP_BODY_BYTES)
+        .read_to_end(&mut body)
+        .ok()?;
+
+    Some(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// The async counterpart to [agent], used with the `async` feature.
+#[cfg(feature = "async")]
+fn client(follow_redirects: bool) -> &'static reqwest::Client {
+    static FOLLOWING: OnceLock<reqwest::Client> = OnceLock::new();
+    static NOT_FOLLOWING: OnceLock<reqwest::Client> = OnceLock::new();
+
+    let client = if follow_redirects {
+        &FOLLOWING
+    } else {
+        &NOT_FOLLOWING
+    };
+    client.get_or_init(|| {
+        // A limit of zero would make a redirect an error, rather than the response
+        let policy = if follow_redirects {
+            reqwest::redirect::Policy::limited(MAX_HTTP_REDIRECTS as usize)
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+        reqwest::Client::builder()
+            .redirect(policy)
+            .build()
+            .expect("the HTTP client's TLS backend failed to initialize")
+    })
+}
+
+/// Creates an async request configured according to `options`.
+#[cfg(feature = "async")]
+pub(crate) fn request_async(
+    method: reqwest::Method,
+    url: &str,
+    options: &HttpOptions,
+) -> reqwest::RequestBuilder {
+    let request = client(options.follow_redirects)
+        .request(method, url)
+        .timeout(options.timeout);
+    match &options.auth {
+        Some(auth) => request.basic_auth(&auth.username, Some(&auth.password)),
+        None => request,
+    }
+}
+
+/// Sends `request` and gets the status code of its response, like [status].
+#[cfg(feature = "async")]
+pub(crate) async fn status_async(request: reqwest::RequestBuilder) -> Option<u16> {
+    let response = request.send().await.ok()?;
+    let status = response.status().as_u16();
+    drain_async(response).await;
+    Some(status)
+}
+
+/// The async counterpart to [multi_status], requesting every URL concurrently
+/// with `All`, and stopping at the first that doesn't return `status`.
+#[cfg(feature = "async")]
+pub(crate) async fn multi_status_async(
+    urls: &[String],
+    status: u16,
+    mode: MultiMode,
+    options: &HttpOptions,
+) -> bool {
+    match mode {
+        MultiMode::Any => {
+            for url in urls {
+                let request = request_async(reqwest::Method::GET, url, options);
+                if status_async(request).await == Some(status) {
+                    return true;
+                }
+            }
+            false
+        }
+        MultiMode::All => {
+            let mut checks = tokio::task::JoinSet::new();
+            for url in urls {
+                checks.spawn(status_async(request_async(
+                    reqwest::Method::GET,
+                    url,
+                    options,
+                )));
+            }
+            // Dropping the set aborts whatever requests are left
+            while let Some(returned) = checks.join_next().await {
+                if returned.ok().flatten() != Some(status) {
+                    return false;
+                }
+            }
+            true
+        }
+    }
+}
+
+/// Sends `request` and gets the value of the header `name` from its response,
+/// like [header].
+#[cfg(feature = "async")]
+pub(crate) async fn header_async(
+    request: reqwest::RequestBuilder,
+    name: &str,
+) -> Option<Option<String>> {
+    let response = request.send().await.ok()?;
+    let value = response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    drain_async(response).await;
+    Some(value)
+}
+
+/// Reads and discards (up to [MAX_HTTP_BODY_BYTES] of) `response`'s body, like
+/// [drain]. As with the blocking agent, the connection is only reused once the
+/// body is read.
+#[cfg(feature = "async")]
+async fn drain_async(mut response: reqwest::Response) {
+    let mut read = 0;
+    while read < MAX_HTTP_BODY_BYTES {
+        match response.chunk().await {
+            Ok(Some(chunk)) => read += chunk.len() as u64,
+            _ => break,
+        }
+    }
+}
+
+/// Sends `request` and reads (up to [MAX_HTTP_BODY_BYTES] of) its response's
+/// body, like [body].
+#[cfg(feature = "async")]
+pub(crate) async fn body_async(request: reqwest::RequestBuilder) -> Option<String> {
+    let mut response = request.send().await.ok()?;
+
+    let mut body = Vec::new();
+    while (body.len() as u64) < MAX_HTTP_BODY_BYTES {
+        match response.chunk().await.ok()? {
+            Some(chunk) => body.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    body.truncate(MAX_HTTP_BODY_BYTES as usize);
+
+    Some(String::from_utf8_lossy(&body).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn base64() {
+        use super::base64;
+
+        // From RFC 4648
+        let encoded: Vec<String> = ["", "f", "fo", "foo", "foob", "fooba", "foobar"]
+            .iter()
+            .map(|s| base64(s.as_bytes()))
+            .collect();
+        assert_eq!(
+            encoded,
+            ["", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=", "Zm9vYmFy"]
+        );
+    }
+
+    #[test]
+    fn basic_auth() {
+        use super::BasicAuth;
+
+        let auth = BasicAuth {
+            username: "Aladdin".to_string(),
+            password: "open sesame".to_string(),
+        };
+        assert_eq!(auth.header(), "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+        assert!(!format!("{auth:?}").contains("sesame"));
+    }
+
+    #[test]
+    fn pooled() {
+        use crate::{testing::serve_http_keep_alive, Wait};
+        use std::sync::atomic::Ordering;
+
+        let (url, accepted) = serve_http_keep_alive();
+        let w = Wait::new_http_get(url, 200);
+        for _ in 0..5 {
+            assert!(w.condition_met());
+        }
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+}
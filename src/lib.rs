@@ -1,4 +1,37 @@
+#[cfg(feature = "async")]
+mod asynchronous;
+mod builder;
+pub use builder::{ConfiguredWait, WaitBuilder, DEFAULT_POLL_INTERVAL};
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "http")]
+pub use http::{
+    BasicAuth, HttpOptions, MultiMode, StatusClass, DEFAULT_HTTP_TIMEOUT, MAX_HTTP_BODY_BYTES,
+    MAX_HTTP_REDIRECTS,
+};
+mod parse;
+pub use parse::{ParseError, WaitParseError};
+mod poll;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use poll::set_log_skipped;
+#[cfg(feature = "rand")]
+pub use poll::Jittered;
+#[cfg(feature = "ctrlc")]
+pub use poll::WaitResult;
+pub use poll::{
+    set_min_interval, ExponentialBackoff, FixedInterval, PollSchedule, DEFAULT_MIN_INTERVAL,
+};
+mod process;
+pub use process::{spawn_and_wait_ready, wait_command_output};
+mod stdin;
+pub use stdin::StdinLines;
+#[cfg(any(unix, windows, feature = "sysinfo"))]
+mod system;
+#[cfg(all(test, feature = "http"))]
+mod testing;
+#[cfg(feature = "tls")]
+mod tls;
 mod wait;
 pub use wait::*;
 mod waits;
-pub use waits::Waits;
+pub use waits::{WaitOutcome, Waits, WaitsStatus};
@@ -0,0 +1,349 @@
+use crate::{Wait, Waits};
+use std::{fmt, iter::Peekable, str::CharIndices, str::FromStr};
+
+/// An error from parsing a [Waits] expression with [Waits::parse], or a single
+/// [Wait] condition with [str::parse].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The expression ended where a condition or `(` was expected.
+    UnexpectedEnd,
+    /// An operator or parenthesis was found at byte offset `position` where it
+    /// isn't allowed.
+    UnexpectedToken { position: usize, token: String },
+    /// The `(` at byte offset `position` is never closed.
+    UnclosedParen { position: usize },
+    /// The quoted argument starting at byte offset `position` is never closed.
+    UnclosedQuote { position: usize },
+    /// A condition isn't of the form `kind:argument`, or `kind` isn't recognized.
+    UnknownCondition(String),
+    /// The argument to `elapsed:` isn't a valid duration.
+    InvalidDuration(String),
+    /// The argument to `tcp:` isn't a valid `host:port`.
+    InvalidHost(String),
+    /// The argument to `http:` has an invalid status.
+    #[cfg(feature = "http")]
+    InvalidHttp(crate::HttpArgError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ParseError::UnexpectedToken { position, token } => {
+                write!(f, "unexpected '{token}' at position {position}")
+            }
+            ParseError::UnclosedParen { position } => {
+                write!(f, "'(' at position {position} is never closed")
+            }
+            ParseError::UnclosedQuote { position } => {
+                write!(f, "quote at position {position} is never closed")
+            }
+            ParseError::UnknownCondition(c) => write!(f, "unknown condition '{c}'"),
+            ParseError::InvalidDuration(d) => write!(f, "invalid duration '{d}'"),
+            ParseError::InvalidHost(h) => write!(f, "invalid host:port '{h}'"),
+            #[cfg(feature = "http")]
+            ParseError::InvalidHttp(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The error from parsing a single [Wait] with [str::parse]. This is the same as
+/// a [ParseError], since a [Wait] is parsed like a condition in a [Waits]
+/// expression.
+pub type WaitParseError = ParseError;
+
+impl Waits {
+    /// Parses an expression combining conditions with `&`, `|`, `!`, and
+    /// parentheses, such as `"exists:foo.txt & !elapsed:10s"`.
+    ///
+    /// `!` binds tightest, then `&`, then `|`, so `!a & b | c` is `((!a) & b) | c`.
+    /// Conditions are written as `kind:argument`, where `kind` is one of:
+    ///
+    /// * `exists:PATH` -- see [Wait::new_file_exists]
+    /// * `elapsed:DURATION` -- see [crate::parse_duration]
+    /// * `tcp:HOST:PORT` -- see [Wait::new_tcp_connect]
+    /// * `http:CODE,URL` -- see [crate::parse_http_get] (requires the `http` feature)
+    ///
+    /// An argument ends at whitespace, `&`, `|`, or a parenthesis. To include
+    /// any of these, surround the argument with double quotes:
+    ///
+    /// ```
+    /// use waitforit::Waits;
+    ///
+    /// let w = Waits::parse(r#"exists:"my file.txt" | (tcp:localhost:80 & !elapsed:1m)"#);
+    /// assert!(w.is_ok());
+    /// ```
+    pub fn parse(expr: &str) -> Result<Waits, ParseError> {
+        let mut parser = Parser {
+            chars: expr.char_indices().peekable(),
+        };
+
+        let waits = parser.parse_or()?;
+        match parser.next_token()? {
+            None => Ok(waits),
+            Some((position, token)) => Err(ParseError::UnexpectedToken {
+                position,
+                token: token.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for Wait {
+    type Err = WaitParseError;
+
+    /// Parses a single `kind:argument` condition, in any of the forms accepted
+    /// by [Waits::parse], such as `"elapsed:10s"` or `"tcp:localhost:80"`.
+    /// Surrounding whitespace is ignored, but the argument isn't unquoted.
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let w: Wait = "exists:/tmp/ready".parse().unwrap();
+    /// assert!(w == Wait::new_file_exists("/tmp/ready"));
+    /// assert!("exists".parse::<Wait>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_leaf(s.trim())
+    }
+}
+
+/// Parses a single `kind:argument` condition.
+fn parse_leaf(leaf: &str) -> Result<Wait, ParseError> {
+    let (kind, arg) = leaf
+        .split_once(':')
+        .ok_or_else(|| ParseError::UnknownCondition(leaf.to_string()))?;
+
+    match kind {
+        "exists" => Ok(Wait::new_file_exists(arg)),
+        "elapsed" => Wait::new_elapsed_from_str(arg)
+            .map_err(|_| ParseError::InvalidDuration(arg.to_string())),
+        "tcp" => Wait::try_new_tcp_connect(arg).map_err(|e| ParseError::InvalidHost(e.0)),
+        #[cfg(feature = "http")]
+        "http" => {
+            let (status, url) = crate::parse_http_get(arg).map_err(ParseError::InvalidHttp)?;
+            Ok(Wait::new_http_get(url, status))
+        }
+        _ => Err(ParseError::UnknownCondition(leaf.to_string())),
+    }
+}
+
+enum Token {
+    /// One of `&|!()`
+    Op(char),
+    /// A `kind:argument` condition, with quotes removed
+    Leaf(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Op(c) => write!(f, "{c}"),
+            Token::Leaf(leaf) => write!(f, "{leaf}"),
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    /// or := and ('|' and)*
+    fn parse_or(&mut self) -> Result<Waits, ParseError> {
+        let mut waits = self.parse_and()?;
+        while self.next_op_is('|') {
+            waits = waits | self.parse_and()?;
+        }
+        Ok(waits)
+    }
+
+    /// and := unary ('&' unary)*
+    fn parse_and(&mut self) -> Result<Waits, ParseError> {
+        let mut waits = self.parse_unary()?;
+        while self.next_op_is('&') {
+            waits = waits & self.parse_unary()?;
+        }
+        Ok(waits)
+    }
+
+    /// unary := '!' unary | '(' or ')' | leaf
+    fn parse_unary(&mut self) -> Result<Waits, ParseError> {
+        match self.next_token()? {
+            None => Err(ParseError::UnexpectedEnd),
+            Some((_, Token::Op('!'))) => Ok(!self.parse_unary()?),
+            Some((position, Token::Op('('))) => {
+                let waits = self.parse_or()?;
+                match self.next_token()? {
+                    Some((_, Token::Op(')'))) => Ok(waits),
+                    _ => Err(ParseError::UnclosedParen { position }),
+                }
+            }
+            Some((position, Token::Op(token))) => Err(ParseError::UnexpectedToken {
+                position,
+                token: token.to_string(),
+            }),
+            Some((_, Token::Leaf(leaf))) => parse_leaf(&leaf).map(Waits::from),
+        }
+    }
+
+    /// Consumes the next token if it's the operator `op`.
+    fn next_op_is(&mut self, op: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|&(_, c)| c == op).is_some()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn next_token(&mut self) -> Result<Option<(usize, Token)>, ParseError> {
+        self.skip_whitespace();
+        let position = match self.chars.peek() {
+            None => return Ok(None),
+            Some(&(i, c @ ('&' | '|' | '!' | '(' | ')'))) => {
+                self.chars.next();
+                return Ok(Some((i, Token::Op(c))));
+            }
+            Some(&(i, _)) => i,
+        };
+
+        let mut leaf = String::new();
+        while let Some(&(i, c)) = self.chars.peek() {
+            match c {
+                '"' => {
+                    self.chars.next();
+                    loop {
+                        match self.chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, c)) => leaf.push(c),
+                            None => return Err(ParseError::UnclosedQuote { position: i }),
+                        }
+                    }
+                }
+                '&' | '|' | '(' | ')' => break,
+                c if c.is_whitespace() => break,
+                c => {
+                    self.chars.next();
+                    leaf.push(c);
+                }
+            }
+        }
+
+        Ok(Some((position, Token::Leaf(leaf))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseError;
+    use crate::{Wait, Waits};
+
+    fn leaves(w: &Waits) -> Vec<String> {
+        w.leaves().map(|w| format!("{w:?}")).collect()
+    }
+
+    #[test]
+    fn precedence() {
+        let w = Waits::parse("!exists:a & exists:b | exists:c").unwrap();
+        let Waits::Or(ww) = w else {
+            panic!("expected Or")
+        };
+        assert!(matches!(ww.0, Waits::And(_)));
+        assert!(matches!(
+            ww.1,
+            Waits::Single(Wait::Exists { not: false, .. })
+        ));
+
+        let w = Waits::parse("!(exists:a | exists:b)").unwrap();
+        assert!(matches!(w, Waits::And(_)));
+    }
+
+    #[test]
+    fn quoted() {
+        let w = Waits::parse(r#"exists:"a b&c" & tcp:localhost:80"#).unwrap();
+        let leaves = leaves(&w);
+        assert!(leaves[0].contains(r#"path: "a b&c""#));
+        assert!(leaves[1].contains(r#"host: "localhost:80""#));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Waits::parse("").err(), Some(ParseError::UnexpectedEnd));
+        assert_eq!(
+            Waits::parse("exists:a &").err(),
+            Some(ParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Waits::parse("(exists:a").err(),
+            Some(ParseError::UnclosedParen { position: 0 })
+        );
+        assert_eq!(
+            Waits::parse("exists:a)").err(),
+            Some(ParseError::UnexpectedToken {
+                position: 8,
+                token: ")".to_string()
+            })
+        );
+        assert_eq!(
+            Waits::parse("exists:a exists:b").err(),
+            Some(ParseError::UnexpectedToken {
+                position: 9,
+                token: "exists:b".to_string()
+            })
+        );
+        assert_eq!(
+            Waits::parse(r#"exists:"a"#).err(),
+            Some(ParseError::UnclosedQuote { position: 7 })
+        );
+        assert_eq!(
+            Waits::parse("nope:a").err(),
+            Some(ParseError::UnknownCondition("nope:a".to_string()))
+        );
+        assert_eq!(
+            Waits::parse("elapsed:10x").err(),
+            Some(ParseError::InvalidDuration("10x".to_string()))
+        );
+        assert_eq!(
+            Waits::parse("tcp:localhost").err(),
+            Some(ParseError::InvalidHost("localhost".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        use super::WaitParseError;
+
+        let w: Wait = " exists:/tmp/ready ".parse().unwrap();
+        assert!(w == Wait::new_file_exists("/tmp/ready"));
+        let w: Wait = "elapsed:10s".parse().unwrap();
+        assert!(matches!(w, Wait::Elapsed { not: false, .. }));
+        assert!(!w.condition_met());
+        let w: Wait = "tcp:localhost:80".parse().unwrap();
+        assert!(w == Wait::new_tcp_connect("localhost:80"));
+        #[cfg(feature = "http")]
+        {
+            let w: Wait = "http:204,http://localhost/".parse().unwrap();
+            assert!(w == Wait::new_http_get("http://localhost/", 204));
+            assert_eq!(
+                "http:600,http://localhost/".parse::<Wait>().err(),
+                Some(ParseError::InvalidHttp(
+                    crate::HttpArgError::StatusOutOfRange("600".to_string())
+                ))
+            );
+        }
+
+        let err: WaitParseError = "".parse::<Wait>().unwrap_err();
+        assert_eq!(err, ParseError::UnknownCondition(String::new()));
+        assert_eq!(
+            "elapsed:soon".parse::<Wait>().err(),
+            Some(ParseError::InvalidDuration("soon".to_string()))
+        );
+        assert_eq!(
+            "tcp:localhost".parse::<Wait>().err(),
+            Some(ParseError::InvalidHost("localhost".to_string()))
+        );
+    }
+}
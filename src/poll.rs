@@ -0,0 +1,765 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Decides how long to wait between checks of a condition.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use waitforit::{ExponentialBackoff, Wait};
+///
+/// let db_up = Wait::new_tcp_connect("localhost:5432");
+/// let backoff = ExponentialBackoff {
+///     initial: Duration::from_millis(50),
+///     factor: 2.0,
+///     max: Duration::from_secs(5),
+/// };
+///
+/// db_up.wait_with_schedule(&backoff);
+/// ```
+pub trait PollSchedule {
+    /// The interval to wait after the check numbered `attempt` (counting from
+    /// zero) wasn't met. `last` is the interval returned for the previous
+    /// attempt, or zero for the first.
+    ///
+    /// As with a fixed interval, time spent checking the condition counts
+    /// toward the interval.
+    fn next_interval(&self, attempt: u32, last: Duration) -> Duration;
+}
+
+/// Checks a condition every `.0`, which is what [crate::Wait::wait] does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedInterval(pub Duration);
+
+impl PollSchedule for FixedInterval {
+    fn next_interval(&self, _attempt: u32, _last: Duration) -> Duration {
+        self.0
+    }
+}
+
+/// Checks a condition after `initial`, then multiplies the interval by `factor`
+/// after each check, up to `max`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExponentialBackoff {
+    pub initial: Duration,
+    pub factor: f64,
+    pub max: Duration,
+}
+
+impl PollSchedule for ExponentialBackoff {
+    fn next_interval(&self, attempt: u32, last: Duration) -> Duration {
+        if attempt == 0 {
+            return self.initial.min(self.max);
+        }
+
+        // An interval too large to represent (or a negative factor) tops out
+        Duration::try_from_secs_f64(last.as_secs_f64() * self.factor)
+            .unwrap_or(self.max)
+            .min(self.max)
+    }
+}
+
+/// Randomizes the intervals of another [PollSchedule] by up to `jitter` either
+/// way, so that many processes polling the same thing don't fall into lockstep.
+/// The result is never negative.
+///
+/// Jitter alone only spreads checks out around the same cadence, so it's best
+/// combined with [ExponentialBackoff]. The underlying schedule is given the
+/// previous, randomized interval as `last`.
+#[cfg(feature = "rand")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Jittered<S> {
+    pub schedule: S,
+    pub jitter: Duration,
+}
+
+#[cfg(feature = "rand")]
+impl<S: PollSchedule> PollSchedule for Jittered<S> {
+    fn next_interval(&self, attempt: u32, last: Duration) -> Duration {
+        let interval = self.schedule.next_interval(attempt, last).as_secs_f64();
+        let offset = rand::random_range(-1.0..=1.0) * self.jitter.as_secs_f64();
+        Duration::try_from_secs_f64((interval + offset).max(0.0)).unwrap_or(Duration::MAX)
+    }
+}
+
+/// Wraps `condition` to report on the wait for `what`, which checks every
+/// `interval` (if it's fixed):
+///
+/// * With the `log` feature, once `condition` is met, an `info!` record says that
+///   `what` was met and how long that took.
+/// * With the `tracing` feature, each check happens in a `wait` span, which lasts
+///   as long as the wrapper, and emits an event with its result.
+///
+/// Without either, this is just `condition`.
+pub(crate) fn instrumented<'a, F>(
+    what: &'a dyn Display,
+    interval: Option<Duration>,
+    mut condition: F,
+) -> impl FnMut() -> bool + 'a
+where
+    F: FnMut() -> bool + 'a,
+{
+    #[cfg(feature = "log")]
+    let first = Instant::now();
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!("wait", condition = %what, interval = ?interval);
+    #[cfg(not(feature = "tracing"))]
+    let _ = interval;
+    #[cfg(not(any(feature = "log", feature = "tracing")))]
+    let _ = what;
+
+    move || {
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        let met = condition();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(met, "checked");
+        #[cfg(feature = "log")]
+        if met {
+            log::info!("{what} met after {:?}", first.elapsed());
+        }
+        met
+    }
+}
+
+/// Whether to log the [Wait](crate::Wait)s skipped by short-circuiting. See
+/// [set_log_skipped].
+#[cfg(any(feature = "log", feature = "tracing"))]
+static LOG_SKIPPED: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether checking a [Waits](crate::Waits) also logs the
+/// [Wait](crate::Wait)s that it didn't check because it short-circuited (see
+/// [Waits::condition_met](crate::Waits::condition_met)), which it doesn't by
+/// default. They're logged like the checked ones, but as `skipped`, eg,
+/// `tcp("db:5432"): skipped`, so that it's clear from the log why a condition
+/// was never polled. With the `tracing` feature, each is a trace-level event
+/// with a `skipped` field.
+///
+/// This applies to the whole process, and to the blocking checks only.
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub fn set_log_skipped(enabled: bool) {
+    LOG_SKIPPED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [set_log_skipped] is enabled.
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) fn log_skipped() -> bool {
+    LOG_SKIPPED.load(Ordering::Relaxed)
+}
+
+/// The default for [set_min_interval].
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The shortest interval between checks, in nanoseconds. See [set_min_interval].
+static MIN_INTERVAL_NANOS: AtomicU64 = AtomicU64::new(DEFAULT_MIN_INTERVAL.as_nanos() as u64);
+
+/// Sets the shortest interval that blocking and async waits leave between the
+/// starts of two checks, which is [DEFAULT_MIN_INTERVAL] by default. Any
+/// shorter interval, including one from a [PollSchedule], is raised to this, so
+/// that an interval of `Duration::ZERO` doesn't spin a CPU (or hammer a server
+/// with requests) as fast as it can check.
+///
+/// This applies to the whole process. Setting it to zero removes the floor.
+pub fn set_min_interval(min: Duration) {
+    MIN_INTERVAL_NANOS.store(saturating_nanos(min), Ordering::Relaxed);
+}
+
+/// `duration` in nanoseconds, or [u64::MAX] if it's longer than that.
+fn saturating_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// `interval`, raised to the minimum set by [set_min_interval].
+fn at_least_min(interval: Duration) -> Duration {
+    interval.max(Duration::from_nanos(
+        MIN_INTERVAL_NANOS.load(Ordering::Relaxed),
+    ))
+}
+
+/// Blocks until `condition` returns true, checking every `interval`. Returns the
+/// time from the first check starting to the satisfied check finishing.
+pub(crate) fn wait<F>(condition: F, interval: Duration) -> Duration
+where
+    F: FnMut() -> bool,
+{
+    wait_with_schedule(condition, &FixedInterval(interval))
+}
+
+/// Blocks until `condition` returns true, checking every `interval`. Returns how
+/// many checks it took, counting the satisfied one and saturating at [u32::MAX].
+pub(crate) fn wait_counted<F>(mut condition: F, interval: Duration) -> u32
+where
+    F: FnMut() -> bool,
+{
+    let mut checks = 0u32;
+    wait(
+        || {
+            checks = checks.saturating_add(1);
+            condition()
+        },
+        interval,
+    );
+    checks
+}
+
+/// Blocks until `condition` returns true, checking at intervals decided by
+/// `schedule`. Returns the time from the first check starting to the satisfied
+/// check finishing.
+pub(crate) fn wait_with_schedule<F>(mut condition: F, schedule: &dyn PollSchedule) -> Duration
+where
+    F: FnMut() -> bool,
+{
+    let first = Instant::now();
+    let mut interval = Duration::ZERO;
+    for attempt in 0.. {
+        let start = Instant::now();
+        if condition() {
+            break;
+        }
+
+        interval = at_least_min(schedule.next_interval(attempt, interval));
+        let loop_time = start.elapsed();
+        if interval > loop_time {
+            std::thread::sleep(interval - loop_time);
+        }
+    }
+
+    first.elapsed()
+}
+
+/// Checks `condition` up to `max_attempts` times, every `interval`, until it
+/// returns true. Returns whether the condition was met.
+///
+/// There's no sleep after the last attempt, and zero attempts means no checks at
+/// all.
+pub(crate) fn wait_max_attempts<F>(mut condition: F, interval: Duration, max_attempts: u32) -> bool
+where
+    F: FnMut() -> bool,
+{
+    let interval = at_least_min(interval);
+    for attempt in 1..=max_attempts {
+        let start = Instant::now();
+        if condition() {
+            return true;
+        }
+
+        let loop_time = start.elapsed();
+        if attempt < max_attempts && interval > loop_time {
+            std::thread::sleep(interval - loop_time);
+        }
+    }
+
+    false
+}
+
+/// How often a cancellable wait checks for cancellation while sleeping.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Blocks until `condition` returns true or `cancel` is set, checking every
+/// `interval`. Returns whether the condition was met.
+///
+/// `cancel` is checked before each check of the condition and throughout the
+/// sleeps between them, so cancellation is noticed promptly even with a long
+/// `interval`.
+pub(crate) fn wait_cancellable<F>(condition: F, interval: Duration, cancel: &AtomicBool) -> bool
+where
+    F: FnMut() -> bool,
+{
+    wait_until_cancelled(condition, interval, || cancel.load(Ordering::Relaxed))
+}
+
+/// Like [wait_cancellable], but the wait is cancelled once `cancelled` returns
+/// true.
+fn wait_until_cancelled<F, C>(mut condition: F, interval: Duration, cancelled: C) -> bool
+where
+    F: FnMut() -> bool,
+    C: Fn() -> bool,
+{
+    let interval = at_least_min(interval);
+    loop {
+        if cancelled() {
+            return false;
+        }
+
+        let start = Instant::now();
+        if condition() {
+            return true;
+        }
+
+        while let Some(remaining) = interval.checked_sub(start.elapsed()) {
+            if remaining.is_zero() || cancelled() {
+                break;
+            }
+            std::thread::sleep(remaining.min(CANCEL_CHECK_INTERVAL));
+        }
+    }
+}
+
+/// How an interruptible wait, such as [Wait::wait_interruptible](crate::Wait::wait_interruptible),
+/// ended.
+#[cfg(feature = "ctrlc")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitResult {
+    /// The condition was met.
+    Met,
+    /// Ctrl-C was pressed first.
+    Interrupted,
+}
+
+/// How many times Ctrl-C has been pressed since the handler was installed.
+#[cfg(feature = "ctrlc")]
+static INTERRUPTS: AtomicU64 = AtomicU64::new(0);
+
+/// Blocks until `condition` returns true or Ctrl-C is pressed, checking every
+/// `interval`. The first call installs the Ctrl-C handler.
+///
+/// Presses are counted rather than flagged so that concurrent waits are all
+/// interrupted by the same press, and a press before a wait starts doesn't
+/// interrupt it.
+#[cfg(feature = "ctrlc")]
+pub(crate) fn wait_interruptible<F>(condition: F, interval: Duration) -> WaitResult
+where
+    F: FnMut() -> bool,
+{
+    static HANDLER: std::sync::Once = std::sync::Once::new();
+    HANDLER.call_once(|| {
+        // If the program has its own handler, presses just aren't seen here
+        let _ = ctrlc::set_handler(|| {
+            INTERRUPTS.fetch_add(1, Ordering::SeqCst);
+        });
+    });
+
+    let before = INTERRUPTS.load(Ordering::SeqCst);
+    if wait_until_cancelled(condition, interval, || {
+        INTERRUPTS.load(Ordering::SeqCst) != before
+    }) {
+        WaitResult::Met
+    } else {
+        WaitResult::Interrupted
+    }
+}
+
+/// Asynchronously waits until `condition` resolves to true, checking every
+/// `interval` and sleeping on the tokio timer in between.
+#[cfg(feature = "async")]
+pub(crate) async fn wait_async<F, Fut>(mut condition: F, interval: Duration)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = bool>,
+{
+    let interval = at_least_min(interval);
+    loop {
+        let start = Instant::now();
+        if condition().await {
+            return;
+        }
+
+        let loop_time = start.elapsed();
+        if interval > loop_time {
+            tokio::time::sleep(interval - loop_time).await;
+        }
+    }
+}
+
+/// Blocks until `condition` returns true or `timeout` has elapsed, checking every
+/// `interval`. Returns whether the condition was met.
+///
+/// The condition is always checked at least once, so a zero `timeout` amounts to
+/// a single check.
+pub(crate) fn wait_timeout<F>(mut condition: F, interval: Duration, timeout: Duration) -> bool
+where
+    F: FnMut() -> bool,
+{
+    let interval = at_least_min(interval);
+    let start = Instant::now();
+    loop {
+        let loop_start = Instant::now();
+        if condition() {
+            return true;
+        }
+
+        // Time spent in `condition` counts against the deadline
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return false;
+        }
+
+        let loop_time = loop_start.elapsed();
+        if interval > loop_time {
+            // Don't sleep past the deadline; we'll check one last time when it arrives
+            std::thread::sleep((interval - loop_time).min(timeout - elapsed));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn wait_elapsed() {
+        let checks = Cell::new(0);
+        let elapsed = super::wait(
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() == 3
+            },
+            Duration::from_millis(20),
+        );
+
+        assert!(elapsed >= Duration::from_millis(40));
+        assert!(elapsed < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn wait_counted() {
+        let checks = Cell::new(0);
+        let counted = super::wait_counted(
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() == 4
+            },
+            Duration::ZERO,
+        );
+        assert_eq!(counted, 4);
+        assert_eq!(super::wait_counted(|| true, Duration::ZERO), 1);
+    }
+
+    /// Held by the tests that change the minimum interval or depend on its
+    /// default, since it applies to the whole process.
+    static MIN_INTERVAL_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn min_interval() {
+        use super::DEFAULT_MIN_INTERVAL;
+
+        let _lock = MIN_INTERVAL_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        // A zero interval doesn't check as fast as possible
+        let start = Instant::now();
+        assert!(!super::wait_max_attempts(|| false, Duration::ZERO, 6));
+        assert!(start.elapsed() >= 5 * DEFAULT_MIN_INTERVAL);
+
+        let checks = Cell::new(0);
+        let start = Instant::now();
+        assert!(!super::wait_timeout(
+            || {
+                checks.set(checks.get() + 1);
+                false
+            },
+            Duration::ZERO,
+            Duration::from_millis(20),
+        ));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(checks.get() <= 21);
+    }
+
+    #[test]
+    fn set_min_interval() {
+        use super::{saturating_nanos, set_min_interval, DEFAULT_MIN_INTERVAL};
+
+        /// Restores the default, even if the test fails.
+        struct Restore;
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                set_min_interval(DEFAULT_MIN_INTERVAL);
+            }
+        }
+
+        let _lock = MIN_INTERVAL_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let _restore = Restore;
+
+        set_min_interval(Duration::from_millis(5));
+        let start = Instant::now();
+        assert!(!super::wait_max_attempts(|| false, Duration::ZERO, 3));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+
+        // Zero removes the floor, so these don't take 100 * DEFAULT_MIN_INTERVAL
+        set_min_interval(Duration::ZERO);
+        let start = Instant::now();
+        assert!(!super::wait_max_attempts(|| false, Duration::ZERO, 100));
+        assert!(start.elapsed() < 50 * DEFAULT_MIN_INTERVAL);
+
+        // Setting the floor that high would stall every other test, so only the
+        // conversion is checked
+        assert_eq!(saturating_nanos(Duration::from_millis(10)), 10_000_000);
+        assert_eq!(saturating_nanos(Duration::from_nanos(u64::MAX)), u64::MAX);
+        assert_eq!(saturating_nanos(Duration::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        use super::{ExponentialBackoff, PollSchedule};
+
+        let backoff = ExponentialBackoff {
+            initial: Duration::from_millis(50),
+            factor: 2.0,
+            max: Duration::from_millis(300),
+        };
+
+        let mut last = Duration::ZERO;
+        let intervals: Vec<_> = (0..5)
+            .map(|attempt| {
+                last = backoff.next_interval(attempt, last);
+                last.as_millis()
+            })
+            .collect();
+        assert_eq!(intervals, [50, 100, 200, 300, 300]);
+
+        let huge = ExponentialBackoff {
+            factor: f64::MAX,
+            max: Duration::MAX,
+            ..backoff
+        };
+        assert_eq!(huge.next_interval(1, Duration::from_secs(1)), Duration::MAX);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn jittered() {
+        use super::{FixedInterval, Jittered, PollSchedule};
+
+        let jittered = Jittered {
+            schedule: FixedInterval(Duration::from_millis(100)),
+            jitter: Duration::from_millis(50),
+        };
+        for attempt in 0..100 {
+            let interval = jittered.next_interval(attempt, Duration::ZERO);
+            assert!(interval >= Duration::from_millis(50));
+            assert!(interval <= Duration::from_millis(150));
+        }
+
+        // Clamped to zero rather than going negative
+        let wide = Jittered {
+            schedule: FixedInterval(Duration::ZERO),
+            jitter: Duration::from_secs(1),
+        };
+        assert!((0..100)
+            .all(|attempt| wide.next_interval(attempt, Duration::ZERO) <= Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn wait_with_schedule() {
+        use super::PollSchedule;
+
+        struct Recorder(Cell<Vec<u32>>);
+        impl PollSchedule for Recorder {
+            fn next_interval(&self, attempt: u32, _last: Duration) -> Duration {
+                let mut attempts = self.0.take();
+                attempts.push(attempt);
+                self.0.set(attempts);
+                Duration::ZERO
+            }
+        }
+
+        let checks = Cell::new(0);
+        let schedule = Recorder(Cell::new(Vec::new()));
+        super::wait_with_schedule(
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() == 4
+            },
+            &schedule,
+        );
+
+        assert_eq!(schedule.0.take(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn max_attempts() {
+        let checks = Cell::new(0);
+        let count = || {
+            checks.set(checks.get() + 1);
+            checks.get() == 3
+        };
+
+        assert!(!super::wait_max_attempts(count, Duration::ZERO, 0));
+        assert_eq!(checks.get(), 0);
+        assert!(!super::wait_max_attempts(count, Duration::ZERO, 2));
+        assert_eq!(checks.get(), 2);
+        assert!(super::wait_max_attempts(count, Duration::ZERO, 10));
+        assert_eq!(checks.get(), 3);
+
+        // No sleep after the last attempt
+        let start = Instant::now();
+        assert!(!super::wait_max_attempts(
+            || false,
+            Duration::from_secs(10),
+            1
+        ));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn cancellable() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        assert!(super::wait_cancellable(
+            || true,
+            Duration::from_secs(10),
+            &cancel
+        ));
+
+        let c = cancel.clone();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            c.store(true, Ordering::Relaxed);
+        });
+
+        let start = Instant::now();
+        assert!(!super::wait_cancellable(
+            || false,
+            Duration::from_secs(10),
+            &cancel
+        ));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        canceller.join().unwrap();
+
+        // Already cancelled, so the condition isn't checked at all
+        assert!(!super::wait_cancellable(
+            || panic!(),
+            Duration::ZERO,
+            &cancel
+        ));
+    }
+
+    #[test]
+    fn timeout_zero_checks_once() {
+        let checks = Cell::new(0);
+        let met = super::wait_timeout(
+            || {
+                checks.set(checks.get() + 1);
+                false
+            },
+            Duration::from_secs(1),
+            Duration::ZERO,
+        );
+
+        assert!(!met);
+        assert_eq!(checks.get(), 1);
+    }
+
+    #[test]
+    fn timeout_met() {
+        let checks = Cell::new(0);
+        let met = super::wait_timeout(
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() == 3
+            },
+            Duration::from_millis(1),
+            Duration::from_secs(10),
+        );
+
+        assert!(met);
+        assert_eq!(checks.get(), 3);
+    }
+
+    #[test]
+    fn timeout_does_not_oversleep() {
+        let start = Instant::now();
+        let met = super::wait_timeout(|| false, Duration::from_secs(10), Duration::from_millis(50));
+
+        assert!(!met);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[cfg(all(unix, feature = "ctrlc"))]
+    #[test]
+    fn interruptible() {
+        use super::{wait_interruptible, WaitResult};
+
+        // The first wait installs the handler, after which SIGINT is safe to send
+        let met = wait_interruptible(|| true, Duration::from_millis(1));
+        assert_eq!(met, WaitResult::Met);
+
+        std::thread::spawn(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            unsafe { libc::kill(libc::getpid(), libc::SIGINT) };
+        });
+        let start = Instant::now();
+        let interrupted = wait_interruptible(|| false, Duration::from_secs(10));
+        assert_eq!(interrupted, WaitResult::Interrupted);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // An earlier press doesn't interrupt a new wait
+        let checks = Cell::new(0);
+        let met = wait_interruptible(
+            || {
+                checks.set(checks.get() + 1);
+                checks.get() == 3
+            },
+            Duration::from_millis(1),
+        );
+        assert_eq!(met, WaitResult::Met);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn logged() {
+        use crate::Wait;
+        use std::sync::Mutex;
+
+        static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        struct Logger;
+        impl log::Log for Logger {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+            fn log(&self, record: &log::Record) {
+                let line = format!("{} {}", record.level(), record.args());
+                RECORDS.lock().unwrap().push(line);
+            }
+            fn flush(&self) {}
+        }
+        log::set_logger(&Logger).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let checks = Cell::new(0);
+        let w = Wait::new_dns_resolves("logged.invalid")
+            | Wait::new_custom_closure(|| true) & !Wait::new_env_var("WAITFORIT_LOGGED");
+        w.wait_with_callback(Duration::from_millis(1), |_, _| {
+            checks.set(checks.get() + 1)
+        });
+        assert_eq!(checks.get(), 1);
+
+        let records = RECORDS.lock().unwrap().clone();
+        let records: Vec<_> = records
+            .iter()
+            .filter(|r| r.to_lowercase().contains("logged"))
+            .collect();
+        assert_eq!(records.len(), 3, "{records:?}");
+        assert_eq!(records[0], r#"DEBUG dns("logged.invalid"): not met"#);
+        assert_eq!(records[1], r#"DEBUG NOT env("WAITFORIT_LOGGED"): met"#);
+        assert!(records[2].starts_with(
+            r#"INFO dns("logged.invalid") OR (custom() AND NOT env("WAITFORIT_LOGGED")) met after "#
+        ));
+
+        super::set_log_skipped(true);
+        let w = Wait::new_never() & Wait::new_env_var("WAITFORIT_LOGGED_SKIPPED");
+        assert!(!w.condition_met());
+        super::set_log_skipped(false);
+        assert!(!w.condition_met());
+
+        let records = RECORDS.lock().unwrap();
+        let records: Vec<_> = records.iter().filter(|r| r.contains("SKIPPED")).collect();
+        assert_eq!(
+            records,
+            [r#"DEBUG env("WAITFORIT_LOGGED_SKIPPED"): skipped"#]
+        );
+    }
+}
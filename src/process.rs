@@ -0,0 +1,395 @@
+//! Process inspection and execution.
+
+use crate::{poll, Waits};
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+    process::{Child, Command, Output, Stdio},
+    time::Duration,
+};
+
+/// Runs `program` with `args` to completion, discarding its output, and returns its
+/// exit code. Returns `None` if it couldn't be run or was killed by a signal.
+pub(crate) fn exit_code(program: &str, args: &[String]) -> Option<i32> {
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok()?
+        .code()
+}
+
+/// Runs `program` with `args` every `interval` until it exits successfully (with
+/// code 0), returning the output of that run, or `None` if `timeout` elapses
+/// first. This will block the thread.
+///
+/// This is for commands whose output is needed once they work, such as
+/// retrying `kubectl get` until the resource exists:
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use waitforit::wait_command_output;
+///
+/// let output = wait_command_output(
+///     "kubectl",
+///     ["get", "secret", "db-password", "-o", "jsonpath={.data}"],
+///     Duration::from_secs(2),
+///     Duration::from_secs(60),
+/// );
+/// if let Some(output) = output {
+///     println!("{}", String::from_utf8_lossy(&output.stdout));
+/// }
+/// ```
+///
+/// The command's stdout and stderr are captured and its stdin is null. A
+/// command that can't be run at all (eg, `program` doesn't exist) is retried
+/// like a failing one. As with [Wait::wait_timeout](crate::Wait::wait_timeout),
+/// the command is run at least once, even with a zero `timeout`.
+pub fn wait_command_output<P, I, A>(
+    program: P,
+    args: I,
+    interval: Duration,
+    timeout: Duration,
+) -> Option<Output>
+where
+    P: AsRef<OsStr>,
+    I: IntoIterator<Item = A>,
+    A: AsRef<OsStr>,
+{
+    let args: Vec<OsString> = args.into_iter().map(|a| a.as_ref().to_owned()).collect();
+    let mut output = None;
+    let succeeded = poll::wait_timeout(
+        || {
+            output = Command::new(&program)
+                .args(&args)
+                .stdin(Stdio::null())
+                .output()
+                .ok();
+            output.as_ref().is_some_and(|o| o.status.success())
+        },
+        interval,
+        timeout,
+    );
+
+    if succeeded {
+        output
+    } else {
+        None
+    }
+}
+
+/// Starts `program` with `args`, then waits for `ready`, checking every
+/// `interval`, and returns the still-running process once it's met. This will
+/// block the thread. For example, to start a server and wait until it's
+/// listening:
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use waitforit::{spawn_and_wait_ready, Wait};
+///
+/// let mut server = spawn_and_wait_ready(
+///     "redis-server",
+///     ["--port", "6380"],
+///     Wait::new_tcp_connect("localhost:6380"),
+///     Duration::from_millis(100),
+///     Duration::from_secs(30),
+/// )?;
+/// // ... use the server, then stop it
+/// server.kill()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// The process inherits stdout and stderr, and its stdin is null. If `timeout`
+/// elapses before `ready` is met, the process is killed and an error of kind
+/// [io::ErrorKind::TimedOut] is returned. If the process exits before `ready`
+/// is met, an error is returned as soon as that's noticed. Errors starting the
+/// process are returned as they are.
+pub fn spawn_and_wait_ready<P, I, A, W>(
+    program: P,
+    args: I,
+    ready: W,
+    interval: Duration,
+    timeout: Duration,
+) -> io::Result<Child>
+where
+    P: AsRef<OsStr>,
+    I: IntoIterator<Item = A>,
+    A: AsRef<OsStr>,
+    W: Into<Waits>,
+{
+    let ready = ready.into();
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .spawn()?;
+
+    let mut exited = None;
+    let finished = poll::wait_timeout(
+        poll::instrumented(&ready, Some(interval), || {
+            if ready.condition_met() {
+                return true;
+            }
+            exited = child.try_wait().ok().flatten();
+            exited.is_some()
+        }),
+        interval,
+        timeout,
+    );
+
+    if let Some(status) = exited {
+        return Err(io::Error::other(format!(
+            "process exited with {status} before it was ready"
+        )));
+    }
+    if !finished {
+        // It may have exited just now, in which case there's nothing to kill
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("process wasn't ready after {timeout:?}"),
+        ));
+    }
+
+    Ok(child)
+}
+
+/// Checks whether the process `pid` has exited. If we aren't permitted to query
+/// it, it's assumed to have exited.
+#[cfg(unix)]
+pub(crate) fn pid_exited(pid: u32) -> bool {
+    let pid = match libc::pid_t::try_from(pid) {
+        Ok(pid) if pid > 0 => pid,
+        // Not a valid PID, so there's no such process
+        _ => return true,
+    };
+
+    // Signal 0 checks for existence (and permission) without sending anything
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return false;
+    }
+
+    // ESRCH means there's no such process; EPERM means we can't query it
+    let errno = std::io::Error::last_os_error().raw_os_error();
+    errno == Some(libc::ESRCH) || errno == Some(libc::EPERM)
+}
+
+/// Checks whether the process `pid` has exited. If we aren't permitted to query
+/// it, it's assumed to have exited.
+#[cfg(windows)]
+pub(crate) fn pid_exited(pid: u32) -> bool {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, STILL_ACTIVE},
+        System::Threading::{GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+    };
+
+    // If the process can't be opened, it either doesn't exist or we lack permission
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if handle.is_null() {
+        return true;
+    }
+
+    let mut code = 0;
+    let queried = unsafe { GetExitCodeProcess(handle, &mut code) } != 0;
+    unsafe { CloseHandle(handle) };
+
+    !queried || code != STILL_ACTIVE as u32
+}
+
+/// Checks whether any running process's executable is named `name`.
+#[cfg(target_os = "linux")]
+pub(crate) fn process_named(name: &str) -> bool {
+    let entries = match std::fs::read_dir("/proc") {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        // Only the numeric entries are processes
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        {
+            return false;
+        }
+
+        // `comm` is truncated to 15 bytes, so also check the basename of argv[0]
+        let comm = std::fs::read_to_string(path.join("comm")).unwrap_or_default();
+        if comm.trim_end_matches('\n') == name {
+            return true;
+        }
+
+        let cmdline = std::fs::read(path.join("cmdline")).unwrap_or_default();
+        let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+        let argv0 = String::from_utf8_lossy(argv0);
+        argv0.rsplit('/').next() == Some(name)
+    })
+}
+
+/// Checks whether any running process's executable is named `name`, ignoring case
+/// and an `.exe` extension.
+#[cfg(windows)]
+pub(crate) fn process_named(name: &str) -> bool {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, INVALID_HANDLE_VALUE},
+        System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+            TH32CS_SNAPPROCESS,
+        },
+    };
+
+    let name = name.to_lowercase();
+    let matches = |exe: &str| {
+        let exe = exe.to_lowercase();
+        exe == name || exe.strip_suffix(".exe") == Some(name.as_str())
+    };
+
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return false;
+    }
+
+    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+
+    let mut found = false;
+    let mut more = unsafe { Process32FirstW(snapshot, &mut entry) } != 0;
+    while more && !found {
+        let len = entry
+            .szExeFile
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        found = matches(&String::from_utf16_lossy(&entry.szExeFile[..len]));
+        more = unsafe { Process32NextW(snapshot, &mut entry) } != 0;
+    }
+
+    unsafe { CloseHandle(snapshot) };
+    found
+}
+
+/// Checks whether any running process's executable is named `name`.
+#[cfg(all(not(target_os = "linux"), not(windows), feature = "sysinfo"))]
+pub(crate) fn process_named(name: &str) -> bool {
+    use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+    system.processes().values().any(|p| p.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    #[cfg(unix)]
+    #[test]
+    fn wait_command_output() {
+        use super::wait_command_output;
+
+        let output = wait_command_output(
+            "sh",
+            ["-c", "echo ready"],
+            Duration::from_millis(1),
+            Duration::from_secs(10),
+        );
+        assert_eq!(output.unwrap().stdout, b"ready\n");
+
+        // Succeeds once the file exists, on a later attempt
+        let path = std::env::temp_dir().join(format!("waitforit-output-{}", std::process::id()));
+        let script = format!("cat '{}'", path.display());
+        let writer = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                std::fs::write(path, "contents").unwrap();
+            })
+        };
+        let output = wait_command_output(
+            "sh",
+            ["-c", &script],
+            Duration::from_millis(5),
+            Duration::from_secs(10),
+        );
+        writer.join().unwrap();
+        assert_eq!(output.unwrap().stdout, b"contents");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn spawn_and_wait_ready() {
+        use super::spawn_and_wait_ready;
+        use crate::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-spawn-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let script = format!("sleep 0.1; touch '{}'; exec sleep 10", path.display());
+        let mut child = spawn_and_wait_ready(
+            "sh",
+            ["-c", &script],
+            Wait::new_file_exists(&path),
+            Duration::from_millis(5),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(path.exists());
+        assert!(child.try_wait().unwrap().is_none());
+        child.kill().unwrap();
+        child.wait().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Killed once the timeout elapses
+        let start = Instant::now();
+        let err = spawn_and_wait_ready(
+            "sleep",
+            ["10"],
+            Wait::new_never(),
+            Duration::from_millis(5),
+            Duration::from_millis(50),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Or given up on as soon as it exits
+        let start = Instant::now();
+        let err = spawn_and_wait_ready(
+            "sh",
+            ["-c", "exit 3"],
+            Wait::new_never(),
+            Duration::from_millis(5),
+            Duration::from_secs(10),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("before it was ready"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        assert!(spawn_and_wait_ready(
+            "waitforit-nonexistent-program",
+            Vec::<String>::new(),
+            Wait::new_always(),
+            Duration::from_millis(5),
+            Duration::from_secs(1),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn wait_command_output_timeout() {
+        let start = Instant::now();
+        let output = super::wait_command_output(
+            "waitforit-nonexistent-program",
+            Vec::<String>::new(),
+            Duration::from_millis(5),
+            Duration::from_millis(50),
+        );
+        assert!(output.is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,184 @@
+//! Reading lines from stdin in the background, for
+//! [Wait::StdinLine](crate::Wait::StdinLine).
+
+use std::{
+    io::BufRead,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, Once, PoisonError, Weak,
+    },
+};
+
+/// The lines read from stdin (without their line endings) that a
+/// [Wait::StdinLine](crate::Wait::StdinLine) has yet to see, and whether it's
+/// already seen one that matched. Clones share the same lines and result.
+///
+/// Stdin is read by a single background thread, started when the first of these
+/// is created, which sends each line to every `StdinLines` in existence. Each
+/// only gets the lines read after it was created.
+#[derive(Clone)]
+pub struct StdinLines(Arc<Mutex<State>>);
+
+struct State {
+    receiver: Receiver<String>,
+    matched: bool,
+}
+
+/// A [StdinLines] that's sent lines, and the sender for them. It's gone once all
+/// its clones are, even if the reader hasn't noticed yet.
+type Subscriber = (Sender<String>, Weak<Mutex<State>>);
+
+/// Where the background reader sends each line of stdin.
+static SUBSCRIBERS: Mutex<Vec<Subscriber>> = Mutex::new(Vec::new());
+
+impl StdinLines {
+    /// Starts getting the lines read from stdin, starting the background reader
+    /// if it isn't running yet.
+    pub(crate) fn subscribe() -> Self {
+        static READER: Once = Once::new();
+
+        let (sender, receiver) = mpsc::channel();
+        let lines = Self::new(receiver);
+        lines.add_to(
+            &mut SUBSCRIBERS.lock().unwrap_or_else(PoisonError::into_inner),
+            sender,
+        );
+        READER.call_once(|| {
+            std::thread::spawn(read_stdin);
+        });
+
+        lines
+    }
+
+    /// Adds this to `subscribers`, to be sent lines with `sender`. Those that are
+    /// gone are removed first, since otherwise they're only noticed when a line
+    /// is sent, so they'd pile up while stdin is quiet or once it's closed.
+    fn add_to(&self, subscribers: &mut Vec<Subscriber>, sender: Sender<String>) {
+        subscribers.retain(|(_, lines)| lines.strong_count() > 0);
+        subscribers.push((sender, Arc::downgrade(&self.0)));
+    }
+
+    fn new(receiver: Receiver<String>) -> Self {
+        Self(Arc::new(Mutex::new(State {
+            receiver,
+            matched: false,
+        })))
+    }
+
+    /// Whether any line read so far contains `needle`. Once one has, this is
+    /// true until [Self::reset].
+    pub(crate) fn contains(&self, needle: &str) -> bool {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        while !state.matched {
+            match state.receiver.try_recv() {
+                Ok(line) => state.matched = line.contains(needle),
+                // Nothing more yet, or stdin was closed
+                Err(_) => break,
+            }
+        }
+        state.matched
+    }
+
+    /// Forgets whether a line has matched. Lines that have already been read
+    /// aren't seen again.
+    pub(crate) fn reset(&self) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .matched = false;
+    }
+}
+
+impl std::fmt::Debug for StdinLines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StdinLines(..)")
+    }
+}
+
+/// Reads stdin until it's closed (or can't be read), sending each line to every
+/// subscriber that's still listening. Invalid UTF-8 is replaced rather than
+/// ending the read.
+fn read_stdin() {
+    let mut stdin = std::io::stdin().lock();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match stdin.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&line);
+                let text = text.trim_end_matches(['\n', '\r']);
+                SUBSCRIBERS
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .retain(|(sender, _)| sender.send(text.to_owned()).is_ok());
+            }
+        }
+    }
+
+    // Disconnect everyone, since nothing more will be read
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn contains() {
+        use super::StdinLines;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let lines = StdinLines::new(receiver);
+        assert!(!lines.contains("ready"));
+
+        sender.send("starting".to_string()).unwrap();
+        assert!(!lines.contains("ready"));
+        sender.send("still starting".to_string()).unwrap();
+        sender.send("ready!".to_string()).unwrap();
+        sender.send("ready again".to_string()).unwrap();
+        assert!(lines.clone().contains("ready"));
+
+        // A match is remembered, even once stdin is closed
+        drop(sender);
+        assert!(lines.contains("ready"));
+
+        // ...until it's reset. Lines after the match are still there, but not
+        // those before it
+        lines.reset();
+        assert!(lines.contains("again"));
+        lines.reset();
+        assert!(!lines.contains("ready"));
+    }
+
+    #[test]
+    fn add_to() {
+        use super::StdinLines;
+
+        let mut subscribers = Vec::new();
+        let subscribe = |subscribers: &mut _| {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let lines = StdinLines::new(receiver);
+            lines.add_to(subscribers, sender);
+            lines
+        };
+
+        let first = subscribe(&mut subscribers);
+        let second = subscribe(&mut subscribers);
+        assert_eq!(subscribers.len(), 2);
+
+        // Gone subscribers are pruned as soon as another subscribes, but not
+        // while any clone remains
+        let clone = second.clone();
+        drop((first, second));
+        let third = subscribe(&mut subscribers);
+        assert_eq!(subscribers.len(), 2);
+        drop(clone);
+        let _fourth = subscribe(&mut subscribers);
+        assert_eq!(subscribers.len(), 2);
+
+        subscribers[0].0.send("ready".to_string()).unwrap();
+        assert!(third.contains("ready"));
+    }
+}
@@ -0,0 +1,85 @@
+//! System resource inspection.
+
+use std::path::Path;
+
+/// The space available to this process on the filesystem containing `path`, in
+/// bytes. Returns `None` if it can't be queried, eg, because `path` doesn't exist.
+#[cfg(unix)]
+pub(crate) fn available_disk_space(path: &Path) -> Option<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    // `f_bavail` excludes blocks reserved for root, unlike `f_bfree`
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// The space available to this process on the volume containing `path`, in
+/// bytes, which honors disk quotas. Returns `None` if it can't be queried, eg,
+/// because `path` doesn't exist.
+#[cfg(windows)]
+pub(crate) fn available_disk_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut available = 0;
+    let queried = unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    } != 0;
+
+    queried.then_some(available)
+}
+
+/// The memory available for starting new applications without swapping, in
+/// bytes: `MemAvailable` from `/proc/meminfo`. Returns `None` if it can't be
+/// read, eg, on kernels older than 3.14, which don't report it.
+#[cfg(target_os = "linux")]
+pub(crate) fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib = line.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()?;
+    kib.checked_mul(1024)
+}
+
+/// The physical memory available without writing anything to disk, in bytes:
+/// `ullAvailPhys` from `GlobalMemoryStatusEx`.
+#[cfg(windows)]
+pub(crate) fn available_memory() -> Option<u64> {
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status: MEMORYSTATUSEX = unsafe { std::mem::zeroed() };
+    status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+    let queried = unsafe { GlobalMemoryStatusEx(&mut status) } != 0;
+
+    queried.then_some(status.ullAvailPhys)
+}
+
+/// The memory available for starting new applications without swapping, in
+/// bytes, as reported by the `sysinfo` crate.
+#[cfg(all(not(target_os = "linux"), not(windows), feature = "sysinfo"))]
+pub(crate) fn available_memory() -> Option<u64> {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    Some(system.available_memory())
+}
+
+/// The 1, 5, and 15-minute load averages, from `getloadavg`.
+#[cfg(unix)]
+pub(crate) fn load_averages() -> Option<[f64; 3]> {
+    let mut averages = [0.0; 3];
+    let samples = unsafe { libc::getloadavg(averages.as_mut_ptr(), 3) };
+    (samples == 3).then_some(averages)
+}
@@ -0,0 +1,155 @@
+//! Helpers shared by the unit tests.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+};
+
+/// Builds a raw HTTP/1.1 response with the given status line (eg, `"200 OK"`),
+/// extra headers, and body.
+pub(crate) fn http_response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\n", body.len());
+    for (name, value) in headers {
+        response += &format!("{name}: {value}\r\n");
+    }
+    response += "Connection: close\r\n\r\n";
+    response += body;
+    response
+}
+
+/// Starts a server on a background thread that answers every request with
+/// `response`, returning its base URL.
+pub(crate) fn serve_http(response: String) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            read_request(&stream);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    url
+}
+
+/// Like [serve_http], but also sends the request line (eg, `"GET / HTTP/1.1"`)
+/// of each request it answers to the returned receiver.
+pub(crate) fn serve_http_recorded(response: String) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let (request_line, _) = read_request(&stream);
+            let _ = sender.send(request_line);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    (url, receiver)
+}
+
+/// Starts a server on a background thread that answers every request with an
+/// empty 200, keeping each connection open for more requests, returning its base
+/// URL and how many connections it has accepted so far.
+pub(crate) fn serve_http_keep_alive() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let accepted = Arc::new(AtomicUsize::new(0));
+
+    let counter = accepted.clone();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            counter.fetch_add(1, Ordering::SeqCst);
+            std::thread::spawn(move || {
+                // A closed connection reads as an empty request
+                while !read_request(&stream).0.is_empty() {
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+                    if stream.write_all(response.as_bytes()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    (url, accepted)
+}
+
+/// Starts a server on a background thread that answers every request with 200
+/// if it has basic authentication with `username` and `password`, or with 401
+/// otherwise, returning its base URL.
+pub(crate) fn serve_http_basic_auth(username: &str, password: &str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let expected = format!(
+        "Basic {}",
+        crate::http::base64(format!("{username}:{password}").as_bytes())
+    );
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let (_, headers) = read_request(&stream);
+            let authorized = headers.iter().any(|(name, value)| {
+                name.eq_ignore_ascii_case("authorization") && *value == expected
+            });
+            let response = if authorized {
+                http_response("200 OK", &[], "")
+            } else {
+                http_response("401 Unauthorized", &[("WWW-Authenticate", "Basic")], "")
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    url
+}
+
+/// Reads a request from `stream`, returning its request line (without the line
+/// ending) and headers.
+fn read_request(stream: &TcpStream) -> (String, Vec<(String, String)>) {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let _ = reader.read_line(&mut line);
+    let request_line = line.trim_end().to_owned();
+    line.clear();
+
+    let mut headers = Vec::new();
+    let mut content_length = 0;
+    while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+        if let Some((name, value)) = line.split_once(':') {
+            let (name, value) = (name.trim(), value.trim());
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(0);
+            }
+            headers.push((name.to_owned(), value.to_owned()));
+        }
+        line.clear();
+    }
+    let _ = reader.read_exact(&mut vec![0; content_length]);
+
+    (request_line, headers)
+}
+
+/// Starts a server on a background thread that accepts connections but never
+/// responds, returning its base URL.
+pub(crate) fn serve_nothing() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+
+    url
+}
@@ -0,0 +1,135 @@
+//! TLS certificate inspection, with the `tls` feature.
+
+use crate::validate_host_port;
+use rustls::{pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore};
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Arc, OnceLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The port used when a host doesn't specify one.
+const HTTPS_PORT: u16 = 443;
+
+/// The client configuration shared by all TLS checks, trusting the Mozilla root
+/// certificates from `webpki-roots`.
+fn config() -> Arc<ClientConfig> {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let roots = RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+            };
+            let provider = Arc::new(rustls::crypto::ring::default_provider());
+            let config = ClientConfig::builder_with_provider(provider)
+                .with_safe_default_protocol_versions()
+                .expect("ring supports the default protocol versions")
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            Arc::new(config)
+        })
+        .clone()
+}
+
+/// Splits `host` into the name its certificate is checked against and the
+/// address to connect to, defaulting to port 443.
+fn name_and_address(host: &str) -> (&str, String) {
+    match host.rsplit_once(':') {
+        Some((name, _)) if validate_host_port(host) => {
+            let name = name.strip_prefix('[').unwrap_or(name);
+            (name.strip_suffix(']').unwrap_or(name), host.to_owned())
+        }
+        // An unbracketed IPv6 address
+        _ if host.contains(':') => (host, format!("[{host}]:{HTTPS_PORT}")),
+        _ => (host, format!("{host}:{HTTPS_PORT}")),
+    }
+}
+
+/// Performs a TLS handshake with `host`, returning how much longer the
+/// certificate it presents is valid for. `None` means no handshake could be
+/// completed, including because the certificate isn't trusted or isn't
+/// currently valid.
+///
+/// Connecting and each read and write of the handshake give up after `timeout`.
+pub(crate) fn cert_remaining(host: &str, timeout: Duration) -> Option<Duration> {
+    let (name, address) = name_and_address(host);
+    let server_name = ServerName::try_from(name.to_owned()).ok()?;
+    let mut conn = ClientConnection::new(config(), server_name).ok()?;
+
+    let mut stream = address
+        .to_socket_addrs()
+        .ok()?
+        .find_map(|addr| TcpStream::connect_timeout(&addr, timeout).ok())?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut stream).ok()?;
+    }
+
+    let cert = conn.peer_certificates()?.first()?;
+    let (_, cert) = x509_parser::parse_x509_certificate(cert).ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let validity = cert.validity();
+    remaining(
+        validity.not_before.timestamp(),
+        validity.not_after.timestamp(),
+        now,
+    )
+}
+
+/// How much longer a certificate valid from `not_before` to `not_after` (in
+/// seconds since the Unix epoch) is valid for as of `now`, or `None` if it isn't
+/// valid yet or has expired.
+fn remaining(not_before: i64, not_after: i64, now: u64) -> Option<Duration> {
+    let now = i64::try_from(now).ok()?;
+    if not_before > now {
+        return None;
+    }
+
+    u64::try_from(not_after.checked_sub(now)?)
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn name_and_address() {
+        use super::name_and_address;
+
+        assert_eq!(
+            name_and_address("example.com"),
+            ("example.com", "example.com:443".to_string())
+        );
+        assert_eq!(
+            name_and_address("example.com:8443"),
+            ("example.com", "example.com:8443".to_string())
+        );
+        assert_eq!(
+            name_and_address("[::1]:8443"),
+            ("::1", "[::1]:8443".to_string())
+        );
+        assert_eq!(name_and_address("::1"), ("::1", "[::1]:443".to_string()));
+    }
+
+    #[test]
+    fn remaining() {
+        use super::remaining;
+        use std::time::Duration;
+
+        assert_eq!(remaining(1000, 5000, 2000), Some(Duration::from_secs(3000)));
+        assert_eq!(remaining(1000, 5000, 1000), Some(Duration::from_secs(4000)));
+        assert_eq!(remaining(1000, 5000, 5000), Some(Duration::ZERO));
+
+        // Expired
+        assert_eq!(remaining(1000, 5000, 5001), None);
+        // Not yet valid
+        assert_eq!(remaining(3000, 5000, 2000), None);
+        // Valid since before the epoch
+        assert_eq!(
+            remaining(-1000, 5000, 2000),
+            Some(Duration::from_secs(3000))
+        );
+        assert_eq!(remaining(-1000, -1, 2000), None);
+    }
+}
@@ -1,31 +1,108 @@
+#[cfg(feature = "http")]
+use crate::http::{self, BasicAuth, HttpOptions, MultiMode, StatusClass};
+#[cfg(any(unix, windows, feature = "sysinfo"))]
+use crate::system;
+#[cfg(feature = "tls")]
+use crate::tls;
+use crate::{poll, process, PollSchedule, StdinLines};
 use std::{
-    cell::Cell,
+    net::{Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket},
     path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc, Mutex, PoisonError},
+    thread::JoinHandle,
     time::{Duration, Instant, SystemTime},
 };
 
+#[cfg(feature = "http")]
+use std::ops::RangeInclusive;
 #[cfg(feature = "http")]
 use url::Url;
 
 /// Waits for some condition to be met.
+///
+/// The `new_*` constructors all create conditions that aren't negated; use `!` to
+/// negate one, which each constructor documents the meaning of:
+///
+/// ```
+/// use waitforit::Wait;
+///
+/// let gone = !Wait::new_file_exists("foo.lock");
+/// assert!(matches!(gone, Wait::Exists { not: true, .. }));
+/// ```
+///
+/// The `with_*` methods each configure only the conditions they name. Calling
+/// one on any other condition is a mistake, which panics in debug builds and is
+/// ignored otherwise.
+///
+/// With the `serde` feature, this can be serialized and deserialized, with a few
+/// caveats: `Elapsed` is stored as the time remaining until `end_instant`, runtime
+/// state (eg, the last seen modified time) is reset, and the `Custom` variants
+/// can't be serialized at all.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Wait {
-    /// Waits until `end_instant`. This can be negated, in which case it will
-    /// only trigger until the specified instant.
-    Elapsed { end_instant: Instant, not: bool },
+    /// Waits until `end_instant` (or with `not`, is met only until then). At
+    /// `end_instant` itself, the time has elapsed.
+    Elapsed {
+        #[cfg_attr(feature = "serde", serde(with = "serde_instant"))]
+        end_instant: Instant,
+        not: bool,
+    },
+
+    /// Waits until the system clock reaches `when` (or with `not`, only until
+    /// then).
+    Until { not: bool, when: SystemTime },
+
+    /// Waits until the next time matching the cron expression `cron` (or with
+    /// `not`, only until then). `next` is that time, once it's been computed.
+    #[cfg(feature = "cron")]
+    Schedule {
+        not: bool,
+        cron: String,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        next: SyncCell<Option<SystemTime>>,
+    },
 
     /// Waits until `path` exists (or with `not`, until it no longer exists)
     Exists { not: bool, path: PathBuf },
 
-    /// Waits until a file is updated (or with `not`, until it stops being updated)
+    /// Waits until `path` is a directory (or with `not`, until it isn't)
+    DirExists { not: bool, path: PathBuf },
+
+    /// Waits until `path` is a directory with at least one entry (or with `not`,
+    /// until it isn't)
+    DirNonEmpty { not: bool, path: PathBuf },
+
+    /// Waits until the directory `path` has at least `min_count` entries (or with
+    /// `not`, until it has fewer). Hidden entries are never counted; with
+    /// `files_only`, neither are directories and other non-files; and with
+    /// `extension`, only entries with that extension are.
+    DirFileCount {
+        not: bool,
+        path: PathBuf,
+        min_count: usize,
+        extension: Option<String>,
+        files_only: bool,
+    },
+
+    /// Waits until at least one path matches the glob `pattern` (or with `not`,
+    /// until none do)
+    #[cfg(feature = "glob")]
+    GlobExists { not: bool, pattern: String },
+
+    /// Waits until a file is updated (or with `not`, until it stops being updated).
+    /// With `require_exists`, a missing file doesn't meet the condition either way.
     Update {
         not: bool,
         path: PathBuf,
-        last_update: Cell<Option<SystemTime>>,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        last_update: SyncCell<Option<SystemTime>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        require_exists: bool,
     },
 
-    /// Waits until a file hasn't been updated in some specified [Duration] (or
-    /// with `not`, until it hasn't been updated in at least some Duration).
+    /// Waits until a file has been updated within the last `trigger_duration`
+    /// (or with `not`, until it hasn't been updated for at least that long).
     UpdateSince {
         not: bool,
         path: PathBuf,
@@ -34,31 +111,381 @@ pub enum Wait {
 
     /// Waits until a connection can be made to `host` (or with `not`, until a
     /// connection can no longer be made).
-    TcpHost { not: bool, host: String },
+    TcpHost {
+        not: bool,
+        host: String,
+        #[cfg_attr(feature = "serde", serde(default = "default_tcp_timeout"))]
+        timeout: Duration,
+    },
+
+    /// Waits until `host` resolves to at least one address (or with `not`, until
+    /// it doesn't).
+    DnsResolves { not: bool, host: String },
+
+    /// Waits until `addr` can be bound locally, ie, no other socket is listening
+    /// on it (or with `not`, until it's in use).
+    PortFree { not: bool, addr: String },
+
+    /// Waits until `payload` can be sent to `host` over UDP (or with `not`, until
+    /// it can't). With `expect_reply`, a reply must also be received within
+    /// `timeout`.
+    UdpHost {
+        not: bool,
+        host: String,
+        payload: Vec<u8>,
+        expect_reply: bool,
+        #[cfg_attr(feature = "serde", serde(default = "default_udp_timeout"))]
+        timeout: Duration,
+    },
 
     /// Waits until an HTTP GET to `url` returns `status` (or with `not`, until
     /// it no longer returns that code)
     #[cfg(feature = "http")]
-    HttpGet { not: bool, url: String, status: u16 },
+    HttpGet {
+        not: bool,
+        url: String,
+        status: u16,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until an HTTP GET to `url` returns a status in `statuses` (or with
+    /// `not`, until it returns one outside of it)
+    #[cfg(feature = "http")]
+    HttpGetRange {
+        not: bool,
+        url: String,
+        statuses: RangeInclusive<u16>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until an HTTP POST of `body` to `url` returns `status` (or with
+    /// `not`, until it no longer returns that code)
+    #[cfg(feature = "http")]
+    HttpPost {
+        not: bool,
+        url: String,
+        body: Vec<u8>,
+        content_type: String,
+        status: u16,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until the body of an HTTP GET to `url` contains `contains` (or
+    /// with `not`, until it no longer does)
+    #[cfg(feature = "http")]
+    HttpBody {
+        not: bool,
+        url: String,
+        contains: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until the body of an HTTP GET to `url` matches `pattern` (or with
+    /// `not`, until it no longer does)
+    #[cfg(all(feature = "http", feature = "regex"))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    HttpBodyRegex {
+        not: bool,
+        url: String,
+        pattern: regex::Regex,
+        options: HttpOptions,
+    },
 
-    /// Waits until a file's size has been changed (or with `not`, until it
-    /// stops changing). Nothing is implied about the direction of change.
+    /// Waits until a file's size has changed in the direction given by `change`
+    /// (or with `not`, until it stops changing that way). With `require_exists`,
+    /// a missing file doesn't meet the condition either way.
     FileSize {
         not: bool,
         path: PathBuf,
-        size_bytes: Cell<Option<u64>>,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        size_bytes: SyncCell<Option<u64>>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        change: SizeChange,
+        #[cfg_attr(feature = "serde", serde(default))]
+        require_exists: bool,
+    },
+
+    /// Waits until a file is at least `min_bytes` long (or with `not`, until it's
+    /// shorter). A missing file is never long enough.
+    FileSizeAtLeast {
+        not: bool,
+        path: PathBuf,
+        min_bytes: u64,
+    },
+
+    /// Waits until a line of the file at `path` contains `needle` (or with `not`,
+    /// until none do). `offset` is where the next check starts reading.
+    FileContains {
+        not: bool,
+        path: PathBuf,
+        needle: String,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        offset: SyncCell<u64>,
+    },
+
+    /// Waits until a file's contents hash the same in two consecutive cycles (or
+    /// with `not`, until they differ). While the file can't be read, neither is
+    /// met, and the comparison starts over once it can.
+    FileHashStable {
+        not: bool,
+        path: PathBuf,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        last_hash: SyncCell<Option<u64>>,
+    },
+
+    /// Waits until `path` can be locked exclusively, ie, no one else holds a lock
+    /// on it (or with `not`, until someone does). A missing file can't be
+    /// locked, so it only meets the negated form.
+    FileUnlocked { not: bool, path: PathBuf },
+
+    /// Waits until the filesystem containing `path` has at least `min_bytes`
+    /// available (or with `not`, until it has less). If this can't be queried (eg,
+    /// `path` doesn't exist), it does neither.
+    #[cfg(any(unix, windows))]
+    DiskFree {
+        not: bool,
+        path: PathBuf,
+        min_bytes: u64,
+    },
+
+    /// Waits until the system has at least `min_bytes` of memory available (or
+    /// with `not`, until it has less). If this can't be queried, it does neither.
+    #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+    MemoryFree { not: bool, min_bytes: u64 },
+
+    /// Waits until the system load average over `window` is below `threshold` (or
+    /// with `not`, until it's at least `threshold`). If this can't be queried, it
+    /// does neither.
+    #[cfg(unix)]
+    LoadBelow {
+        not: bool,
+        threshold: f64,
+        window: LoadWindow,
+    },
+
+    /// Waits until a connection can be made to `host` and the first line it sends
+    /// starts with `expect` (or with `not`, until that's no longer the case).
+    /// Connecting and reading the line each give up after `read_timeout`.
+    TcpBanner {
+        not: bool,
+        host: String,
+        expect: String,
+        #[cfg_attr(feature = "serde", serde(default = "default_tcp_timeout"))]
+        read_timeout: Duration,
+    },
+
+    /// Waits until a connection can be made to the Unix domain socket at `path`
+    /// (or with `not`, until a connection can no longer be made).
+    #[cfg(unix)]
+    UnixSocket { not: bool, path: PathBuf },
+
+    /// Waits until the FIFO (named pipe) at `path` has data to read (or with `not`,
+    /// until it doesn't). Nothing that isn't a FIFO is ever ready.
+    #[cfg(unix)]
+    FifoReady { not: bool, path: PathBuf },
+
+    /// Is always met (or with `not`, never is).
+    Always { not: bool },
+
+    /// Is never met (or with `not`, always is).
+    Never { not: bool },
+
+    /// Waits until a TLS handshake with `host` succeeds and its certificate is
+    /// valid for more than `min_remaining` (or with `not`, until it's valid for
+    /// no more than that). A handshake that fails, such as because the
+    /// certificate isn't trusted or has expired, does neither. Connecting and
+    /// each step of the handshake give up after `timeout`.
+    #[cfg(feature = "tls")]
+    TlsCertValid {
+        not: bool,
+        host: String,
+        min_remaining: Duration,
+        #[cfg_attr(feature = "serde", serde(default = "default_tcp_timeout"))]
+        timeout: Duration,
+    },
+
+    /// Waits until the response to an HTTP GET to `url` has the header `header`,
+    /// equal to `equals` if given (or with `not`, until it doesn't)
+    #[cfg(feature = "http")]
+    HttpHeader {
+        not: bool,
+        url: String,
+        header: String,
+        equals: Option<String>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until the body of an HTTP GET to `url` is JSON with the value
+    /// `equals` at the JSON pointer `pointer` (or with `not`, until it has
+    /// some other value or none there). A body that isn't JSON does neither.
+    #[cfg(feature = "json")]
+    HttpJson {
+        not: bool,
+        url: String,
+        pointer: String,
+        equals: serde_json::Value,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until a file was last modified after `reference` (or with `not`,
+    /// until it wasn't). Without a readable modified time, such as when the
+    /// file is missing, neither is met.
+    ModifiedSince {
+        not: bool,
+        path: PathBuf,
+        reference: SystemTime,
+    },
+
+    /// Waits until a line read from stdin contains `needle` (or with `not`, until
+    /// one does not). `lines` is where the lines come from, and whether one has
+    /// matched.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    StdinLine {
+        not: bool,
+        needle: String,
+        lines: StdinLines,
+    },
+
+    /// Waits until the signal `signum` has been delivered to the process (or with
+    /// `not`, while it hasn't). `received` is set by the signal's handler.
+    #[cfg(all(unix, feature = "signal"))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Signal {
+        not: bool,
+        signum: i32,
+        received: SignalFlag,
+    },
+
+    /// Waits until an HTTP GET to `url` gets a successful (2xx) response within
+    /// `max` (or with `not`, until it doesn't). A request that fails counts as
+    /// taking longer than `max`.
+    #[cfg(feature = "http")]
+    HttpLatency {
+        not: bool,
+        url: String,
+        max: Duration,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until an HTTP HEAD to `url` returns `status` (or with `not`, until
+    /// it returns any other status or no response at all)
+    #[cfg(feature = "http")]
+    HttpHead {
+        not: bool,
+        url: String,
+        status: u16,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until a file exists and is empty (or with `not`, until it isn't
+    /// empty). A missing file counts as neither empty nor non-empty.
+    FileEmpty { not: bool, path: PathBuf },
+
+    /// Waits until a file's mode, with only the bits in `mask` kept, equals
+    /// `expect` (or with `not`, until it doesn't). If the file's metadata can't
+    /// be read, the mode matches nothing, negated or not.
+    #[cfg(unix)]
+    FileMode {
+        not: bool,
+        path: PathBuf,
+        mask: u32,
+        expect: u32,
+    },
+
+    /// Waits until HTTP GETs to `urls` return `status`, from any or all of them
+    /// according to `mode` (or with `not`, until they don't)
+    #[cfg(feature = "http")]
+    HttpMulti {
+        not: bool,
+        urls: Vec<String>,
+        status: u16,
+        mode: MultiMode,
+        #[cfg_attr(feature = "serde", serde(default))]
+        options: HttpOptions,
+    },
+
+    /// Waits until the local time is at or after `hour:minute` on a day this
+    /// hasn't already completed on (or with `not`, until it isn't).
+    /// `last_fired_day` is the day it last completed on, as days since 1 CE.
+    #[cfg(feature = "chrono")]
+    TimeOfDay {
+        not: bool,
+        hour: u8,
+        minute: u8,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        last_fired_day: SyncCell<Option<i32>>,
+    },
+
+    /// Waits until the environment variable `name` is set or, if `equals` is
+    /// given, set to that value (or with `not`, until it's not).
+    EnvVar {
+        not: bool,
+        name: String,
+        equals: Option<String>,
+    },
+
+    /// Waits until running `program` with `args` exits with `expected_code` (or
+    /// with `not`, until it exits with any other code).
+    Command {
+        not: bool,
+        program: String,
+        args: Vec<String>,
+        expected_code: i32,
     },
 
     /// Waits until the specified `fn` (not `Fn`) returns true.
+    #[cfg_attr(feature = "serde", serde(skip))]
     Custom { f: fn() -> bool, not: bool },
-    // Pid { pid: u64, },
-    // FileOpen(??), // Check if a handle is open on a particular file (ie, when a file is done being modified)
+
+    /// Waits until the specified closure returns true. Unlike [Wait::Custom],
+    /// the closure may capture state.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    CustomClosure { f: CustomFn, not: bool },
+
+    /// Waits until the specified closure returns true. Unlike
+    /// [Wait::CustomClosure], the closure may mutate its state.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    CustomStateful { f: CustomFnMut, not: bool },
+
+    /// Waits until the process with ID `pid` has exited (or with `not`, until
+    /// it's running).
+    #[cfg(any(unix, windows))]
+    Pid { not: bool, pid: u32 },
+
+    /// Waits until a process named `name` is running (or with `not`, until none
+    /// are).
+    #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+    ProcessNamed { not: bool, name: String },
+
+    /// Waits until `inner` is met (or with `not`, until it isn't), checking it
+    /// at most once per `interval` and reusing its last result in between.
+    Throttled {
+        not: bool,
+        inner: Box<Wait>,
+        interval: Duration,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        last_check: SyncCell<Option<Instant>>,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        last_met: SyncCell<bool>,
+    },
 }
 
 impl Wait {
-    /// Creates a new `Wait` that will complete at `end_instant`.
+    /// Creates a new `Wait` that will complete at `end_instant`: it's met from
+    /// that instant on.
     ///
-    /// Negating this type does nothing.
+    /// When negated, this is met only before `end_instant`, ie, while the
+    /// deadline hasn't yet passed. This is useful to give other conditions a
+    /// window in which to complete (see the `negation` example). Exactly one of
+    /// a condition and its negation is met at any instant.
     pub fn new_elapsed(end_instant: Instant) -> Self {
         Self::Elapsed {
             end_instant,
@@ -67,7 +494,7 @@ impl Wait {
     }
 
     /// Creates a new `Wait` that will complete after `duration` has passed,
-    /// starting immediately.
+    /// starting immediately. See [Self::new_elapsed].
     pub fn new_elapsed_from_duration(duration: Duration) -> Self {
         Self::Elapsed {
             end_instant: std::time::Instant::now() + duration,
@@ -75,11 +502,89 @@ impl Wait {
         }
     }
 
+    /// Creates a new `Wait` that will complete after the duration `duration`
+    /// (eg, `"3h10m"`, as parsed by [parse_duration]) has passed, starting
+    /// immediately. See [Self::new_elapsed_from_duration].
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let w = Wait::new_elapsed_from_str("1m30s").unwrap();
+    /// assert!(!w.condition_met());
+    /// assert!(Wait::new_elapsed_from_str("soon").is_err());
+    /// ```
+    pub fn new_elapsed_from_str(duration: &str) -> Result<Self, ParseDurationError> {
+        parse_duration(duration).map(Self::new_elapsed_from_duration)
+    }
+
+    /// Creates a new `Wait` that completes once the system clock reaches `when`,
+    /// an absolute wall-clock time. This accepts a [SystemTime] or anything that
+    /// converts into one, such as a `chrono::DateTime`:
+    ///
+    /// ```
+    /// use std::time::{Duration, SystemTime};
+    /// use waitforit::Wait;
+    ///
+    /// // 2025-01-01T00:00:00Z
+    /// let new_year = Wait::new_until(SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_689_600));
+    /// ```
+    ///
+    /// Unlike [Self::new_elapsed], which uses the monotonic clock, this follows
+    /// the system clock, so it's affected if the clock is changed.
+    ///
+    /// When negated, this completes until `when`.
+    pub fn new_until<T>(when: T) -> Self
+    where
+        T: Into<SystemTime>,
+    {
+        Self::Until {
+            not: false,
+            when: when.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes at the next time matching the cron
+    /// expression `cron`. For example, to wait until 02:00:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let two_am = Wait::new_schedule("0 0 2 * * *").unwrap();
+    /// ```
+    ///
+    /// Expressions have six or seven fields -- seconds, minutes, hours, day of
+    /// month, month, day of week, and optionally year -- or are one of the
+    /// shorthands such as `@daily`; see the [cron] crate for details. They're
+    /// validated here, so an invalid expression is reported immediately.
+    ///
+    /// Times are in the system's local time zone, so `"0 0 2 * * *"` means 02:00
+    /// on the local clock, even across daylight saving time transitions. A time
+    /// skipped by a transition doesn't occur that day; one repeated by a
+    /// transition occurs once. The next time is computed on the first check and
+    /// then fixed, so once it passes, this stays complete. An expression with no
+    /// future times never completes.
+    ///
+    /// When negated, this completes until the next matching time has passed.
+    #[cfg(feature = "cron")]
+    pub fn new_schedule<T>(cron: T) -> Result<Self, cron::error::Error>
+    where
+        T: Into<String>,
+    {
+        let cron = cron.into();
+        cron.parse::<cron::Schedule>()?;
+
+        Ok(Self::Schedule {
+            not: false,
+            cron,
+            next: SyncCell::new(None),
+        })
+    }
+
     /// Creates a new `Wait` that completes when an HTTP GET to `url` returns
     /// the specified `status` code.
     ///
     /// When negated, this completes when an HTTP GET to `url` returns any
-    /// other status value.
+    /// other status value, or when no response can be received at all.
     #[cfg(feature = "http")]
     pub fn new_http_get<T>(url: T, status: u16) -> Self
     where
@@ -89,422 +594,5676 @@ impl Wait {
             not: false,
             url: url.into(),
             status,
+            options: HttpOptions::default(),
         }
     }
 
-    /// Creates a new `Wait` that completes when a TCP connection can be
-    /// established to `host`.
+    /// Creates a new `Wait` that completes when an HTTP GET to `url` returns a
+    /// status code within `statuses`.
     ///
-    /// When `not` is specified, this completes when a TCP connection can no
-    /// longer be established.
-    pub fn new_tcp_connect<T>(host: T) -> Self
+    /// When negated, this completes when an HTTP GET to `url` returns a status
+    /// outside of `statuses`, or when no response can be received at all.
+    #[cfg(feature = "http")]
+    pub fn new_http_get_range<T>(url: T, statuses: RangeInclusive<u16>) -> Self
     where
         T: Into<String>,
     {
-        Self::TcpHost {
+        Self::HttpGetRange {
             not: false,
-            host: host.into(),
+            url: url.into(),
+            statuses,
+            options: HttpOptions::default(),
         }
     }
 
-    /// Creates a new `Wait` that completes when the specified file exists.
+    /// Creates a new `Wait` that completes when an HTTP GET to `url` returns a
+    /// status code in the given class. For example, to wait for an outage:
     ///
-    /// When negated, this completes when the file doesn't exist.
-    pub fn new_file_exists<T>(path: T) -> Self
+    /// ```
+    /// use waitforit::{StatusClass, Wait};
+    ///
+    /// let outage = !Wait::new_http_get_class("http://localhost/", StatusClass::Success);
+    /// ```
+    #[cfg(feature = "http")]
+    pub fn new_http_get_class<T>(url: T, class: StatusClass) -> Self
     where
-        T: Into<PathBuf>,
+        T: Into<String>,
     {
-        Self::Exists {
-            not: false,
-            path: path.into(),
-        }
+        Self::new_http_get_range(url, class.range())
     }
 
-    /// Creates a new `Wait` that completes when the specified file is updated
-    /// (according to its [metadata](std::fs::Metadata)'s modified time). In
-    /// other words: as soon as the file is updated, this completes.
+    /// Creates a new `Wait` that completes when an HTTP POST of `body` (with the
+    /// given `Content-Type`) to `url` returns the specified `status` code. The
+    /// same body is sent on every check.
     ///
-    /// When negated, this completes when the metadata has not been updated in
-    /// two consecutive cycles.
-    ///
-    /// Contrast this with [Self::new_file_update_since], which completes when a
-    /// specified duration has passed after the file was last updated.
-    pub fn new_file_update<T>(path: T) -> Self
+    /// When negated, this completes when an HTTP POST to `url` returns any
+    /// other status value, or when no response can be received at all.
+    #[cfg(feature = "http")]
+    pub fn new_http_post<T, B, C>(url: T, body: B, content_type: C, status: u16) -> Self
     where
-        T: Into<PathBuf>,
+        T: Into<String>,
+        B: Into<Vec<u8>>,
+        C: Into<String>,
     {
-        Self::Update {
+        Self::HttpPost {
             not: false,
-            path: path.into(),
-            last_update: Cell::new(None),
+            url: url.into(),
+            body: body.into(),
+            content_type: content_type.into(),
+            status,
+            options: HttpOptions::default(),
         }
     }
 
-    /// Creates a new `Wait` that completes when the specified file is updated
-    /// (according to its [metadata](std::fs::Metadata)'s modified time).
+    /// Creates a new `Wait` that completes when the body of an HTTP GET to `url`
+    /// contains `contains`, regardless of the response's status code. Only the
+    /// first [MAX_HTTP_BODY_BYTES](crate::MAX_HTTP_BODY_BYTES) of the body are checked.
     ///
-    /// When negated, this completes when the metadata has not been updated in
-    /// two consecutive cycles.
-    pub fn new_file_update_since<T>(path: T, trigger_duration: Duration) -> Self
+    /// When negated, this completes when the body doesn't contain `contains`,
+    /// or when no response can be received at all.
+    #[cfg(feature = "http")]
+    pub fn new_http_body<T, C>(url: T, contains: C) -> Self
     where
-        T: Into<PathBuf>,
+        T: Into<String>,
+        C: Into<String>,
     {
-        Self::UpdateSince {
+        Self::HttpBody {
             not: false,
-            path: path.into(),
-            trigger_duration,
+            url: url.into(),
+            contains: contains.into(),
+            options: HttpOptions::default(),
         }
     }
 
-    /// Creates a new `Wait` that completes when the specified file's size is
-    /// updated (according to its [metadata](std::fs::Metadata)'s length).
+    /// Creates a new `Wait` that completes when the body of an HTTP GET to `url`
+    /// matches the regular expression `pattern`, regardless of the response's
+    /// status code. Only the first [MAX_HTTP_BODY_BYTES](crate::MAX_HTTP_BODY_BYTES) of the body are checked.
     ///
-    /// When negated, this completes when the file's length has not been
-    /// updated in two consecutive cycles.
+    /// `pattern` is compiled once, here, and reused for every check, so an
+    /// invalid pattern is reported immediately.
     ///
-    /// If metadata can't be retrieved for this file, this
-    pub fn new_file_size<T>(path: T) -> Self
+    /// When negated, this completes when the body doesn't match `pattern`, or
+    /// when no response can be received at all.
+    #[cfg(all(feature = "http", feature = "regex"))]
+    pub fn new_http_body_regex<T>(url: T, pattern: &str) -> Result<Self, regex::Error>
     where
-        T: Into<PathBuf>,
+        T: Into<String>,
     {
-        Self::FileSize {
+        Ok(Self::HttpBodyRegex {
             not: false,
-            path: path.into(),
-            size_bytes: Cell::new(None),
-        }
+            url: url.into(),
+            pattern: regex::Regex::new(pattern)?,
+            options: HttpOptions::default(),
+        })
     }
 
-    pub fn new_custom(f: fn() -> bool) -> Self {
-        Self::Custom { f, not: false }
+    /// Sets the longest each HTTP request made by this `Wait` may take, overriding
+    /// [DEFAULT_HTTP_TIMEOUT](crate::DEFAULT_HTTP_TIMEOUT). A request that times
+    /// out is treated like any other failure to get a response.
+    #[cfg(feature = "http")]
+    pub fn with_http_timeout(self, timeout: Duration) -> Self {
+        self.with_http_options(|options| options.timeout = timeout)
     }
 
-    //
+    /// Sets whether the HTTP requests made by this `Wait` follow redirects (up to
+    /// [MAX_HTTP_REDIRECTS](crate::MAX_HTTP_REDIRECTS) of them), which they do by
+    /// default. When they don't, a redirect's own status is checked, so a
+    /// misconfigured URL that redirects to a healthy page isn't mistaken for
+    /// healthy, and it's possible to wait until a URL is no longer redirecting:
+    ///
+    /// ```
+    /// use waitforit::{StatusClass, Wait};
+    ///
+    /// let redirecting = Wait::new_http_get_class("http://localhost:8080/", StatusClass::Redirection)
+    ///     .with_follow_redirects(false);
+    /// let settled = !redirecting;
+    /// ```
+    #[cfg(feature = "http")]
+    pub fn with_follow_redirects(self, follow_redirects: bool) -> Self {
+        self.with_http_options(|options| options.follow_redirects = follow_redirects)
+    }
 
-    /// Checks whether this condition is met.
+    /// Sets credentials for the HTTP requests made by this `Wait` to send with
+    /// HTTP basic authentication, such as for a health endpoint behind a login:
     ///
-    /// This is non-blocking, but depending on the variant may have some associated
-    /// delay (eg, an HTTP GET incurs TCP and possibly TLS handshake latency).
-    pub fn condition_met(&self) -> bool {
-        match self {
-            Wait::Elapsed { end_instant, not } => {
-                if *not {
-                    *end_instant >= Instant::now()
-                } else {
-                    *end_instant < Instant::now()
-                }
-            }
-            Wait::Exists { not: true, path } => !Path::new(path).exists(),
-            Wait::Exists { not: false, path } => Path::new(path).exists(),
-            #[cfg(feature = "http")]
-            Wait::HttpGet { not, url, status } => {
-                let result = ureq::get(url).call();
-                if *not {
-                    *status != result.status()
-                } else {
-                    *status == result.status()
-                }
-            }
-            Wait::TcpHost { not: false, host } => std::net::TcpStream::connect(host).is_ok(),
-            Wait::TcpHost { not: true, host } => std::net::TcpStream::connect(host).is_err(),
-            Wait::Update {
-                not,
-                path,
-                last_update,
-            } => {
-                // "Update" checks that the file has (not) been updated in the last 'trigger_duration'
-                let current_modified = match get_modified_time(path) {
-                    Some(systime) => systime,
-                    None => return true, // Can't get the modified time, so we'll assume the condition is met.
-                };
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let healthy = Wait::new_http_get("https://example.com/health", 200)
+    ///     .with_basic_auth("monitor", "hunter2");
+    /// ```
+    ///
+    /// The password is kept out of this `Wait`'s `Debug` and `Display` output
+    /// (see [BasicAuth]). Since basic authentication sends credentials
+    /// unencrypted, it should only be used over HTTPS.
+    #[cfg(feature = "http")]
+    pub fn with_basic_auth<U, P>(self, username: U, password: P) -> Self
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        self.with_http_options(|options| {
+            options.auth = Some(BasicAuth {
+                username: username.into(),
+                password: password.into(),
+            })
+        })
+    }
 
-                match last_update.get() {
-                    Some(last_updated) => {
-                        let is_updated = last_updated != current_modified;
+    /// Changes the options for the HTTP requests made by this `Wait` with `set`.
+    #[cfg(feature = "http")]
+    fn with_http_options(mut self, set: impl FnOnce(&mut HttpOptions)) -> Self {
+        match self.http_options_mut() {
+            Some(options) => set(options),
+            None => debug_assert!(false, "{self} doesn't make HTTP requests"),
+        }
+        self
+    }
 
-                        if *not {
-                            // We want to trigger when the file *isn't* updating.
-                            if is_updated {
-                                // Shouldn't trigger yet, but we should update the last known modified date
-                                last_update.set(Some(current_modified));
-                                false
-                            } else {
-                                // File hasn't updated, so we should trigger
-                                true
-                            }
-                        } else {
-                            // Since not==false: iff the file is updated, we trigger.
-                            // Triggering on an updated mod time doesn't ever need to update the value
-                            is_updated
-                        }
-                    }
-                    None => {
-                        // Haven't tracked the time yet. We'll hang onto it now for the next iteration
-                        last_update.set(Some(current_modified));
-                        false
-                    }
-                }
+    /// The options for the HTTP requests made by this `Wait`, if it makes any.
+    #[cfg(feature = "http")]
+    fn http_options_mut(&mut self) -> Option<&mut HttpOptions> {
+        match self {
+            Wait::HttpGet { options, .. }
+            | Wait::HttpGetRange { options, .. }
+            | Wait::HttpPost { options, .. }
+            | Wait::HttpBody { options, .. }
+            | Wait::HttpHeader { options, .. }
+            | Wait::HttpLatency { options, .. }
+            | Wait::HttpHead { options, .. }
+            | Wait::HttpMulti { options, .. } => Some(options),
+            #[cfg(feature = "json")]
+            Wait::HttpJson { options, .. } => Some(options),
+            #[cfg(feature = "regex")]
+            Wait::HttpBodyRegex { options, .. } => Some(options),
+            _ => None,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when a TCP connection can be
+    /// established to `host`.
+    ///
+    /// Each address that `host` resolves to is tried in turn, in the order the
+    /// system's resolver returns them (eg, `localhost` is often `::1` before
+    /// `127.0.0.1`), giving up on each after [DEFAULT_TCP_TIMEOUT] (see
+    /// [Self::with_tcp_timeout]). The first that connects meets the condition,
+    /// so one dead address doesn't hide a working one.
+    ///
+    /// When negated, this completes when a TCP connection can no longer be
+    /// established to any of the addresses.
+    pub fn new_tcp_connect<T>(host: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::TcpHost {
+            not: false,
+            host: host.into(),
+            timeout: DEFAULT_TCP_TIMEOUT,
+        }
+    }
+
+    /// Like [Self::new_tcp_connect], but checks that `host` is a valid
+    /// `hostname:port` (see [validate_host_port]) up front, so that a typo such as
+    /// a missing port is reported here rather than never connecting.
+    pub fn try_new_tcp_connect<T>(host: T) -> Result<Self, InvalidHost>
+    where
+        T: Into<String>,
+    {
+        let host = host.into();
+        if !validate_host_port(&host) {
+            return Err(InvalidHost(host));
+        }
+
+        Ok(Self::new_tcp_connect(host))
+    }
+
+    /// Sets how long to wait for each TCP connection attempt made by a
+    /// [Wait::TcpHost] or [Wait::TcpBanner] (or, with the `tls` feature, a
+    /// `TlsCertValid`), overriding [DEFAULT_TCP_TIMEOUT].
+    pub fn with_tcp_timeout(mut self, timeout: Duration) -> Self {
+        match &mut self {
+            Wait::TcpHost { timeout: t, .. }
+            | Wait::TcpBanner {
+                read_timeout: t, ..
+            } => *t = timeout,
+            #[cfg(feature = "tls")]
+            Wait::TlsCertValid { timeout: t, .. } => *t = timeout,
+            _ => debug_assert!(false, "{self} doesn't make TCP connections"),
+        }
+        self
+    }
+
+    /// Sets how long a [Wait::UdpHost] waits for a reply to each probe,
+    /// overriding [DEFAULT_UDP_TIMEOUT].
+    pub fn with_udp_timeout(mut self, timeout: Duration) -> Self {
+        match &mut self {
+            Wait::UdpHost { timeout: t, .. } => *t = timeout,
+            _ => debug_assert!(false, "{self} doesn't wait for UDP replies"),
+        }
+        self
+    }
+
+    /// Creates a new `Wait` that completes when `host` resolves to at least one
+    /// address. `host` may be a bare hostname or a `hostname:port`.
+    ///
+    /// A failed lookup and one that returns no addresses are both considered
+    /// "not resolving," so when negated, this completes on either.
+    pub fn new_dns_resolves<T>(host: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::DnsResolves {
+            not: false,
+            host: host.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when a TCP listener can be bound to
+    /// `addr` (eg, `"127.0.0.1:8080"`), such as after the process that held it
+    /// exits. The listener is dropped immediately.
+    ///
+    /// When negated, this completes when `addr` is in use.
+    pub fn new_port_free<T>(addr: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::PortFree {
+            not: false,
+            addr: addr.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when `host` (a `hostname:port`) is
+    /// reachable over UDP: `payload` is sent to it and, if `expect_reply` is set,
+    /// any reply is received within [DEFAULT_UDP_TIMEOUT]. Each address that
+    /// `host` resolves to is tried in turn, and the first that succeeds is enough.
+    ///
+    /// UDP is connectionless, so "reachable" is best-effort. Without
+    /// `expect_reply`, a successful send only means that the packet left this
+    /// host (though some platforms report a prior ICMP "port unreachable" as an
+    /// error). A reply is far stronger evidence, but the service must be one that
+    /// answers `payload`.
+    ///
+    /// When negated, this completes when `host` is no longer reachable.
+    pub fn new_udp_host<T, P>(host: T, payload: P, expect_reply: bool) -> Result<Self, InvalidHost>
+    where
+        T: Into<String>,
+        P: Into<Vec<u8>>,
+    {
+        let host = host.into();
+        if !validate_host_port(&host) {
+            return Err(InvalidHost(host));
+        }
+
+        Ok(Self::UdpHost {
+            not: false,
+            host,
+            payload: payload.into(),
+            expect_reply,
+            timeout: DEFAULT_UDP_TIMEOUT,
+        })
+    }
+
+    /// Creates a new `Wait` that completes when the specified file exists.
+    ///
+    /// When negated, this completes when the file doesn't exist.
+    pub fn new_file_exists<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::Exists {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified path exists and is a
+    /// directory (following symlinks). Unlike [Self::new_file_exists], a file at
+    /// `path` doesn't count.
+    ///
+    /// When negated, this completes when `path` isn't a directory.
+    pub fn new_dir_exists<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::DirExists {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified directory exists
+    /// and contains at least one entry, such as a mounted filesystem or an
+    /// extracted archive. A directory that can't be read (eg, due to
+    /// permissions) isn't considered ready.
+    ///
+    /// When negated, this completes when the directory is empty, missing, or
+    /// can't be read.
+    pub fn new_dir_non_empty<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::DirNonEmpty {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified directory contains
+    /// at least `min_count` regular files (following symlinks), such as a batch of
+    /// inputs accumulating. Use [Self::with_extension] to count only some files
+    /// and [Self::with_files_only] to count subdirectories and the like, too.
+    ///
+    /// Entries whose names start with `.` are considered hidden and are never
+    /// counted, on every platform. This skips editor swap files and the like, as
+    /// well as files that writers often create under a temporary hidden name
+    /// before renaming. A directory that can't be read has no files.
+    ///
+    /// When negated, this completes when the directory has fewer than
+    /// `min_count` files.
+    pub fn new_dir_file_count<T>(path: T, min_count: usize) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::DirFileCount {
+            not: false,
+            path: path.into(),
+            min_count,
+            extension: None,
+            files_only: true,
+        }
+    }
+
+    /// Sets the extension (eg, `"json"`, with or without the leading `.`) that
+    /// entries counted by a [Wait::DirFileCount] must have. Extensions are
+    /// compared case-sensitively.
+    pub fn with_extension<E>(mut self, extension: E) -> Self
+    where
+        E: Into<String>,
+    {
+        match &mut self {
+            Wait::DirFileCount { extension: e, .. } => {
+                let extension = extension.into();
+                *e = Some(
+                    extension
+                        .strip_prefix('.')
+                        .unwrap_or(&extension)
+                        .to_string(),
+                );
             }
-            Wait::UpdateSince {
-                not,
-                path,
-                trigger_duration,
-            } => {
-                // "Update" checks that the file has (not) been updated in the last 'trigger_duration'
-                let last_updated = match get_modified_time(path) {
-                    Some(up) => up,
-                    None => return true, // Can't get the modified time, so we'll assume the condition is met.
-                };
+            _ => debug_assert!(false, "{self} isn't a DirFileCount"),
+        }
+        self
+    }
 
-                let since_last_update = match SystemTime::now().duration_since(last_updated) {
-                    Ok(d) => d,
-                    Err(_) => return true, // can't calculate the duration, so we'll assume the condition is met
-                };
+    /// Sets whether a [Wait::DirFileCount] counts only regular files (the
+    /// default) or every entry, including directories.
+    pub fn with_files_only(mut self, files_only: bool) -> Self {
+        match &mut self {
+            Wait::DirFileCount { files_only: f, .. } => *f = files_only,
+            _ => debug_assert!(false, "{self} isn't a DirFileCount"),
+        }
+        self
+    }
+
+    /// Creates a new `Wait` that completes when at least one path matches the glob
+    /// `pattern`, such as `"reports/report-*.csv"`. See the [glob] crate for the
+    /// pattern syntax.
+    ///
+    /// A relative pattern is resolved against the current working directory at
+    /// the time of each check, not when this is created; an absolute pattern is
+    /// used as-is. Paths in directories that can't be read are ignored.
+    ///
+    /// `pattern` is validated here, so an invalid pattern is reported
+    /// immediately.
+    ///
+    /// When negated, this completes when no paths match `pattern`.
+    #[cfg(feature = "glob")]
+    pub fn new_glob_exists<T>(pattern: T) -> Result<Self, glob::PatternError>
+    where
+        T: Into<String>,
+    {
+        let pattern = pattern.into();
+        glob::Pattern::new(&pattern)?;
+
+        Ok(Self::GlobExists {
+            not: false,
+            pattern,
+        })
+    }
+
+    /// Creates a new `Wait` that completes when the specified file is updated
+    /// (according to its [metadata](std::fs::Metadata)'s modified time). In
+    /// other words: as soon as the file is updated, this completes.
+    ///
+    /// When negated, this completes when the metadata has not been updated in
+    /// two consecutive cycles.
+    ///
+    /// If metadata can't be retrieved for this file (eg, it doesn't exist yet),
+    /// this completes immediately, negated or not, unless
+    /// [Self::with_require_exists] is used.
+    ///
+    /// Contrast this with [Self::new_file_update_since], which completes when a
+    /// specified duration has passed after the file was last updated.
+    pub fn new_file_update<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::Update {
+            not: false,
+            path: path.into(),
+            last_update: SyncCell::new(None),
+            require_exists: false,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file has been
+    /// updated (according to its [metadata](std::fs::Metadata)'s modified time)
+    /// within the last `trigger_duration`.
+    ///
+    /// When negated, this completes when the file hasn't been updated for at
+    /// least `trigger_duration`, such as when a writer has finished with it.
+    ///
+    /// If metadata can't be retrieved for this file (eg, it doesn't exist), this
+    /// completes immediately, negated or not.
+    pub fn new_file_update_since<T>(path: T, trigger_duration: Duration) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::UpdateSince {
+            not: false,
+            path: path.into(),
+            trigger_duration,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file's size is
+    /// updated (according to its [metadata](std::fs::Metadata)'s length).
+    ///
+    /// When negated, this completes when the file's length has not been
+    /// updated in two consecutive cycles.
+    ///
+    /// If metadata can't be retrieved for this file (eg, it doesn't exist yet),
+    /// this completes immediately, negated or not, unless
+    /// [Self::with_require_exists] is used.
+    pub fn new_file_size<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::new_file_size_change(path, SizeChange::Any)
+    }
+
+    /// Creates a new `Wait` that completes when the specified file's size changes
+    /// in the direction given by `change`. For example, to wait for a log file to
+    /// be rotated:
+    ///
+    /// ```
+    /// use waitforit::{SizeChange, Wait};
+    ///
+    /// let rotated = Wait::new_file_size_change("server.log", SizeChange::Shrink);
+    /// ```
+    ///
+    /// When negated, this completes when the file's length has not changed in
+    /// that direction in two consecutive cycles.
+    pub fn new_file_size_change<T>(path: T, change: SizeChange) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FileSize {
+            not: false,
+            path: path.into(),
+            size_bytes: SyncCell::new(None),
+            change,
+            require_exists: false,
+        }
+    }
+
+    /// Sets whether a file that doesn't exist (or whose metadata can't be read)
+    /// meets a [Wait::FileSize] or [Wait::Update] condition. By default it does,
+    /// which suits waiting for a file to be deleted.
+    ///
+    /// With `require_exists`, a missing file never meets the condition, and the
+    /// file is tracked afresh once it appears. This suits waiting for a file to be
+    /// written, such as a download, which otherwise "finishes" before it starts:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let downloaded = !Wait::new_file_size("download.zip").with_require_exists(true);
+    /// ```
+    pub fn with_require_exists(mut self, require_exists: bool) -> Self {
+        match &mut self {
+            Wait::FileSize {
+                require_exists: r, ..
+            }
+            | Wait::Update {
+                require_exists: r, ..
+            } => *r = require_exists,
+            _ => debug_assert!(false, "{self} isn't a FileSize or Update"),
+        }
+        self
+    }
+
+    /// Creates a new `Wait` that completes when the specified file is at least
+    /// `min_bytes` long, such as when a download of known size is complete.
+    ///
+    /// Unlike [Self::new_file_size], a file that doesn't exist (or whose
+    /// metadata can't be read) doesn't meet this condition.
+    ///
+    /// When negated, this completes when the file is shorter than `min_bytes`
+    /// or doesn't exist.
+    pub fn new_file_size_at_least<T>(path: T, min_bytes: u64) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FileSizeAtLeast {
+            not: false,
+            path: path.into(),
+            min_bytes,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when any line of the specified file
+    /// contains `needle`, such as a server logging that it's ready:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let started = Wait::new_file_contains("server.log", "Server started");
+    /// ```
+    ///
+    /// The file is read incrementally: lines that don't contain `needle` are
+    /// skipped on later checks, so only newly-appended data is read each cycle.
+    /// If the file shrinks (eg, it's truncated or rotated), it's read again from
+    /// the start. A missing file doesn't contain `needle`.
+    ///
+    /// When negated, this completes when no line contains `needle`.
+    pub fn new_file_contains<T, N>(path: T, needle: N) -> Self
+    where
+        T: Into<PathBuf>,
+        N: Into<String>,
+    {
+        Self::FileContains {
+            not: false,
+            path: path.into(),
+            needle: needle.into(),
+            offset: SyncCell::new(0),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file's contents are
+    /// unchanged between two consecutive cycles. This is the most robust way to
+    /// detect that a file is done being written, since some writers rewrite a
+    /// file without changing its size (see [Self::new_file_size]) and some
+    /// update its modified time without changing its contents (see
+    /// [Self::new_file_update]). The whole file is read and hashed (with a fast,
+    /// non-cryptographic hash) every cycle, so this is costly for large files.
+    ///
+    /// A file that doesn't exist or can't be read doesn't meet the condition,
+    /// and is tracked afresh once it can be read.
+    ///
+    /// When negated, this completes when the file's contents change between two
+    /// consecutive cycles.
+    pub fn new_file_hash_stable<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FileHashStable {
+            not: false,
+            path: path.into(),
+            last_hash: SyncCell::new(None),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file can be locked
+    /// exclusively, such as when a writer that locks it has finished. Each check
+    /// takes the lock and immediately releases it.
+    ///
+    /// This uses the platform's file locks ([std::fs::File::try_lock]). On Unix, these are
+    /// advisory, so only writers that lock the file themselves are noticed. On
+    /// Windows, a file that another process opened without sharing it can't be
+    /// opened, which counts as locked too. A file that doesn't exist can't be
+    /// locked, so doesn't meet this condition.
+    ///
+    /// When negated, this completes when the file is locked (or doesn't exist).
+    pub fn new_file_unlocked<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FileUnlocked {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the filesystem containing `path`
+    /// has at least `min_bytes` of space available, such as before starting a job
+    /// that writes a lot of data. `path` can be any file or directory on that
+    /// filesystem.
+    ///
+    /// The space is what's available to this process: `statvfs`'s `f_bavail` on
+    /// Unix (excluding space reserved for root) and `GetDiskFreeSpaceExW` on
+    /// Windows (honoring disk quotas). If this can't be queried, eg, because
+    /// `path` doesn't exist, the condition isn't met.
+    ///
+    /// When negated, this completes when less than `min_bytes` is available,
+    /// such as for alerting when a disk is filling up. It still isn't met if the
+    /// space can't be queried.
+    #[cfg(any(unix, windows))]
+    pub fn new_disk_free<T>(path: T, min_bytes: u64) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::DiskFree {
+            not: false,
+            path: path.into(),
+            min_bytes,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the system has at least
+    /// `min_bytes` of memory available, such as before starting memory-hungry
+    /// work.
+    ///
+    /// On Linux, "available" is `MemAvailable` from `/proc/meminfo`: the kernel's
+    /// estimate of how much memory can be used without swapping, including page
+    /// cache that can be dropped. This is usually far more than `MemFree`, which
+    /// counts only memory that's entirely unused. On Windows, it's the available
+    /// physical memory from `GlobalMemoryStatusEx`, which includes the standby
+    /// list (ie, cache). Other platforms require the `sysinfo` feature. If this
+    /// can't be queried, the condition isn't met.
+    ///
+    /// When negated, this completes when less than `min_bytes` is available. It
+    /// still isn't met if the memory can't be queried.
+    #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+    pub fn new_memory_free(min_bytes: u64) -> Self {
+        Self::MemoryFree {
+            not: false,
+            min_bytes,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the system load average over
+    /// `window` is below `threshold`, such as to start batch jobs only when a
+    /// shared machine isn't busy. This is checked with `getloadavg`, so it's only
+    /// available on Unix: Windows has no equivalent.
+    ///
+    /// A load average counts the processes running or waiting to run (on Linux,
+    /// also those in uninterruptible sleep, eg, waiting on disk I/O), so it's
+    /// usually compared against the number of CPUs.
+    ///
+    /// When negated, this completes when the load average is at least
+    /// `threshold`.
+    #[cfg(unix)]
+    pub fn new_load_below(threshold: f64, window: LoadWindow) -> Self {
+        Self::LoadBelow {
+            not: false,
+            threshold,
+            window,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when a TCP connection can be
+    /// established to `host` and the first line the server sends starts with
+    /// `expect`, such as `"SSH-2.0"` or an SMTP server's `"220"`. Unlike
+    /// [Self::new_tcp_connect], this shows that the service is actually speaking
+    /// its protocol, not just that something accepted the connection.
+    ///
+    /// Up to [MAX_BANNER_BYTES] are read, stopping at the first newline. Each
+    /// address that `host` resolves to is tried in turn. Connecting and reading
+    /// each give up after [DEFAULT_TCP_TIMEOUT] (see [Self::with_tcp_timeout]), so
+    /// a server that accepts the connection but never sends anything doesn't
+    /// block a check for long.
+    ///
+    /// When negated, this completes when no connection can be made or the
+    /// server sends something else.
+    pub fn new_tcp_banner<T, E>(host: T, expect: E) -> Self
+    where
+        T: Into<String>,
+        E: Into<String>,
+    {
+        Self::TcpBanner {
+            not: false,
+            host: host.into(),
+            expect: expect.into(),
+            read_timeout: DEFAULT_TCP_TIMEOUT,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when a connection can be made to the
+    /// Unix domain socket at `path`, such as `/var/run/app.sock`. This is the
+    /// socket equivalent of [Self::new_tcp_connect].
+    ///
+    /// The socket file existing isn't enough: it's left behind when a server
+    /// exits without removing it, and it's created when the server binds, before
+    /// it starts listening. Each check actually connects, then disconnects.
+    ///
+    /// When negated, this completes when a connection can no longer be made.
+    #[cfg(unix)]
+    pub fn new_unix_socket<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::UnixSocket {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the FIFO (named pipe) at `path`
+    /// has data to read, without reading any of it. Each check opens the FIFO
+    /// without blocking (so it doesn't wait for a writer) and `poll`s it.
+    ///
+    /// A FIFO that no writer has opened yet, or whose writers have all closed it
+    /// without leaving data behind, isn't ready. Nor is a missing file or
+    /// anything other than a FIFO. Note that opening the FIFO to check it lets a
+    /// writer that's blocked opening it proceed, so it's best for the consumer to
+    /// open the FIFO before its writers do.
+    ///
+    /// This is only available on Unix. Windows named pipes work quite
+    /// differently, and would need a separate implementation.
+    ///
+    /// When negated, this completes when the FIFO has no data to read (or isn't
+    /// a FIFO).
+    #[cfg(unix)]
+    pub fn new_fifo_ready<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FifoReady {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that is always met, such as to stand in for a branch of
+    /// a [Waits](crate::Waits) that's built dynamically. This is what
+    /// [Waits::all](crate::Waits::all) of no conditions is.
+    ///
+    /// When negated, this is never met.
+    pub fn new_always() -> Self {
+        Self::Always { not: false }
+    }
+
+    /// Creates a new `Wait` that is never met, such as to stand in for a branch of
+    /// a [Waits](crate::Waits) that's built dynamically. This is what
+    /// [Waits::any](crate::Waits::any) of no conditions is.
+    ///
+    /// When negated, this is always met.
+    pub fn new_never() -> Self {
+        Self::Never { not: false }
+    }
+
+    /// Creates a new `Wait` that completes when `host` (eg, `example.com` or
+    /// `example.com:8443`; the port defaults to 443) presents a trusted TLS
+    /// certificate that's valid for more than `min_remaining`. For example, to
+    /// wait for a rotated certificate to be deployed:
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let thirty_days = Duration::from_secs(30 * 24 * 60 * 60);
+    /// let rotated = Wait::new_tls_cert_valid("example.com", thirty_days);
+    /// ```
+    ///
+    /// Certificates are verified against the Mozilla root certificates, using
+    /// `host` as the server name. Connecting and each step of the handshake give
+    /// up after [DEFAULT_TCP_TIMEOUT] (see [Self::with_tcp_timeout]).
+    ///
+    /// When negated, this completes when the certificate is valid for no more
+    /// than `min_remaining`, such as to alert before it expires. A handshake
+    /// that fails (eg, a self-signed, expired, or not-yet-valid certificate, or
+    /// no TLS server at all) doesn't complete this, negated or not.
+    #[cfg(feature = "tls")]
+    pub fn new_tls_cert_valid<T>(host: T, min_remaining: Duration) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::TlsCertValid {
+            not: false,
+            host: host.into(),
+            min_remaining,
+            timeout: DEFAULT_TCP_TIMEOUT,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the response to an HTTP GET to
+    /// `url` has the header `header` (eg, `X-Ready`), regardless of its value or
+    /// the response's status code. Header names are case-insensitive.
+    ///
+    /// When negated, this completes when the response doesn't have the header,
+    /// or when no response can be received at all.
+    #[cfg(feature = "http")]
+    pub fn new_http_header<T, H>(url: T, header: H) -> Self
+    where
+        T: Into<String>,
+        H: Into<String>,
+    {
+        Self::HttpHeader {
+            not: false,
+            url: url.into(),
+            header: header.into(),
+            equals: None,
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the response to an HTTP GET to
+    /// `url` has the header `header` with exactly the value `equals`, such as
+    /// `X-Ready: true`. The name is case-insensitive, but the value isn't.
+    ///
+    /// When negated, this completes when the response doesn't have the header or
+    /// it has some other value, or when no response can be received at all.
+    #[cfg(feature = "http")]
+    pub fn new_http_header_equals<T, H, E>(url: T, header: H, equals: E) -> Self
+    where
+        T: Into<String>,
+        H: Into<String>,
+        E: Into<String>,
+    {
+        Self::HttpHeader {
+            not: false,
+            url: url.into(),
+            header: header.into(),
+            equals: Some(equals.into()),
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the body of an HTTP GET to `url`
+    /// is JSON with the value `equals` at `pointer`, a [JSON
+    /// pointer](https://datatracker.ietf.org/doc/html/rfc6901), regardless of
+    /// the response's status code. For example, to wait for
+    /// `{"status":{"state":"running"}}`:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let running = Wait::new_http_json("http://localhost:8080/status", "/status/state", "running");
+    /// ```
+    ///
+    /// Only the first [MAX_HTTP_BODY_BYTES](crate::MAX_HTTP_BODY_BYTES) of the
+    /// body are parsed, so a longer body isn't valid JSON.
+    ///
+    /// When negated, this completes when there's some other value at
+    /// `pointer`, or nothing at all, or when no response can be received. A
+    /// body that isn't JSON doesn't complete this, negated or not.
+    #[cfg(feature = "json")]
+    pub fn new_http_json<T, P, E>(url: T, pointer: P, equals: E) -> Self
+    where
+        T: Into<String>,
+        P: Into<String>,
+        E: Into<serde_json::Value>,
+    {
+        Self::HttpJson {
+            not: false,
+            url: url.into(),
+            pointer: pointer.into(),
+            equals: equals.into(),
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file's modified time
+    /// is after `reference`. Unlike [Self::new_file_update], this needs just one
+    /// check to see a change that's already happened.
+    ///
+    /// When negated, this completes when the file's modified time is no later
+    /// than `reference`. If the modified time can't be retrieved (eg, the file
+    /// doesn't exist), this doesn't complete, negated or not.
+    pub fn new_modified_since<T>(path: T, reference: SystemTime) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::ModifiedSince {
+            not: false,
+            path: path.into(),
+            reference,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file is modified
+    /// after now, such as to wait for a config file to be edited:
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let edited = Wait::new_modified_since_now("config.yaml");
+    /// edited.wait(Duration::from_secs(1));
+    /// ```
+    ///
+    /// This is [Self::new_modified_since] with a `reference` of
+    /// [SystemTime::now]. Some filesystems only record modified times to the
+    /// second (or coarser), so a change made very soon after this is created
+    /// may not be seen as after it.
+    pub fn new_modified_since_now<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::new_modified_since(path, SystemTime::now())
+    }
+
+    /// Creates a new `Wait` that completes once a line read from stdin contains
+    /// `needle`, such as a token sent by a user or a piped producer:
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// println!("Type 'go' to continue");
+    /// Wait::new_stdin_line("go").wait(Duration::from_millis(100));
+    /// ```
+    ///
+    /// Reading stdin blocks, so it's read by a background thread that's started
+    /// when the first of these is created, and each check only looks at the
+    /// lines read since the last. Only lines read after this is created are
+    /// seen, and once one matches, this stays complete (see [Self::reset]).
+    /// Since that thread reads all of stdin, nothing else in the process should.
+    ///
+    /// If stdin is closed without a line matching, this never completes, so
+    /// consider waiting with a timeout.
+    ///
+    /// When negated, this completes while no line has contained `needle`.
+    pub fn new_stdin_line<N>(needle: N) -> Self
+    where
+        N: Into<String>,
+    {
+        Self::StdinLine {
+            not: false,
+            needle: needle.into(),
+            lines: StdinLines::subscribe(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes once the signal `signum` (eg,
+    /// `SIGTERM`, from [signal_hook::consts]) has been delivered to the process
+    /// after this is created. Combined with a timeout, this waits for either:
+    ///
+    /// ```no_run
+    /// use signal_hook::consts::SIGTERM;
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let stop = Wait::new_signal(SIGTERM)? | Wait::new_elapsed_from_duration(Duration::from_secs(60));
+    /// stop.wait(Duration::from_millis(100));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// This registers a handler for the signal using the
+    /// [`signal-hook`](https://docs.rs/signal-hook/) crate. A process has only
+    /// one handler for each signal, which `signal-hook` shares between everything
+    /// that registers with it (so any number of these can wait for the same
+    /// signal), but code that installs its own handler (eg, with `sigaction`)
+    /// replaces it. While registered, the handler replaces the signal's default
+    /// action for the whole process, so a `SIGTERM` or `SIGINT` no longer
+    /// terminates it. The handler is unregistered once this `Wait` and all its
+    /// clones are dropped, but even then the default action isn't restored.
+    ///
+    /// Returns an error for signals that can't be handled, such as `SIGKILL`.
+    ///
+    /// When negated, this completes while the signal hasn't been delivered.
+    #[cfg(all(unix, feature = "signal"))]
+    pub fn new_signal(signum: i32) -> std::io::Result<Self> {
+        Ok(Self::Signal {
+            not: false,
+            signum,
+            received: SignalFlag::register(signum)?,
+        })
+    }
+
+    /// Creates a new `Wait` that completes when an HTTP GET to `url` gets a
+    /// successful (2xx) response in less than `max`, timed from sending the
+    /// request until its body has been read.
+    ///
+    /// When negated, this completes when the response takes at least `max`, isn't
+    /// successful, or can't be received at all, such as to detect an endpoint
+    /// degrading.
+    #[cfg(feature = "http")]
+    pub fn new_http_latency<T: Into<String>>(url: T, max: Duration) -> Self {
+        Self::HttpLatency {
+            not: false,
+            url: url.into(),
+            max,
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when an HTTP HEAD to `url` returns
+    /// the specified `status` code. This is like [Wait::new_http_get], but no
+    /// body is sent, so it's lighter for polling large resources.
+    ///
+    /// Some servers don't support HEAD, and return 405 (Method Not Allowed)
+    /// however ready they are. That's just another status, so such a server
+    /// never meets this unless `status` is 405, and always meets it negated.
+    ///
+    /// When negated, this completes when an HTTP HEAD to `url` returns any
+    /// other status value, or when no response can be received at all.
+    #[cfg(feature = "http")]
+    pub fn new_http_head<T>(url: T, status: u16) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::HttpHead {
+            not: false,
+            url: url.into(),
+            status,
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file exists and is
+    /// zero bytes long, such as a flag file that's cleared rather than deleted.
+    ///
+    /// A file that doesn't exist (or whose metadata can't be read) isn't empty,
+    /// and doesn't meet this condition negated either. When negated, this
+    /// completes when the file has any contents.
+    pub fn new_file_empty<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FileEmpty {
+            not: false,
+            path: path.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the specified file's permission
+    /// bits (as from `chmod`), with only the bits in `mask` kept, equal
+    /// `expect`. For example, to wait until a script is executable by everyone
+    /// (`chmod +x`), or until a file is no longer world-writable:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let executable = Wait::new_file_mode("deploy.sh", 0o111, 0o111);
+    /// let not_world_writable = Wait::new_file_mode("config.toml", 0o002, 0);
+    /// ```
+    ///
+    /// A file that doesn't exist (or whose metadata can't be read) doesn't meet
+    /// this condition, negated or not. When negated, this completes when the
+    /// masked bits differ from `expect`.
+    ///
+    /// This is only available on Unix. Windows has no equivalent mode; the
+    /// closest it has is a file's read-only attribute.
+    #[cfg(unix)]
+    pub fn new_file_mode<T>(path: T, mask: u32, expect: u32) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self::FileMode {
+            not: false,
+            path: path.into(),
+            mask,
+            expect,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when HTTP GETs to `urls` return the
+    /// specified `status` code: from at least one of them with [MultiMode::Any],
+    /// or from every one with [MultiMode::All]. For example, to wait until every
+    /// replica behind a load balancer is healthy:
+    ///
+    /// ```
+    /// use waitforit::{MultiMode, Wait};
+    ///
+    /// let replicas = ["http://web-1/health", "http://web-2/health", "http://web-3/health"];
+    /// let all_healthy = Wait::new_http_multi(replicas, 200, MultiMode::All);
+    /// ```
+    ///
+    /// With `Any`, the URLs are requested in order until one returns `status`.
+    /// With `All`, they're requested concurrently. A URL that can't be reached
+    /// doesn't return `status`. With no URLs, `All` is met and `Any` isn't.
+    ///
+    /// When negated, this completes when none (with `Any`) or not all (with
+    /// `All`) of the URLs return `status`.
+    #[cfg(feature = "http")]
+    pub fn new_http_multi<I, T>(urls: I, status: u16, mode: MultiMode) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::HttpMulti {
+            not: false,
+            urls: urls.into_iter().map(Into::into).collect(),
+            status,
+            mode,
+            options: HttpOptions::default(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes the first time each day that the
+    /// local time is at or after `hour:minute` (on a 24-hour clock), such as
+    /// to run a job once a day after 09:00:
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let nine_am = Wait::new_time_of_day(9, 0).unwrap();
+    /// loop {
+    ///     nine_am.wait(Duration::from_secs(30));
+    ///     // run the daily job
+    /// }
+    /// ```
+    ///
+    /// Once it's completed on a day, it isn't met again until that time the next
+    /// day, so the same `Wait` can be waited for again (see [Self::reset] to
+    /// forget that it completed today). Created after the time, it completes on
+    /// the first check that day.
+    ///
+    /// Times are in the system's local time zone, compared on the local clock.
+    /// When a daylight saving time transition skips the time, it completes on
+    /// the first check after the transition; when a transition repeats the time,
+    /// it still completes only once that day.
+    ///
+    /// When negated, this completes on every check until it would have
+    /// completed. Returns `None` if `hour` is more than 23 or `minute` more
+    /// than 59.
+    #[cfg(feature = "chrono")]
+    pub fn new_time_of_day(hour: u8, minute: u8) -> Option<Self> {
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+
+        Some(Self::TimeOfDay {
+            not: false,
+            hour,
+            minute,
+            last_fired_day: SyncCell::new(None),
+        })
+    }
+
+    /// Creates a new `Wait` that completes when the environment variable `name`
+    /// is set (to any valid Unicode value). Since this checks the process's own
+    /// environment, this is useful when another thread sets it.
+    ///
+    /// When negated, this completes when the variable is unset.
+    pub fn new_env_var<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::EnvVar {
+            not: false,
+            name: name.into(),
+            equals: None,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the environment variable `name`
+    /// is set to exactly `value`.
+    ///
+    /// When negated, this completes when the variable is unset or has any other
+    /// value.
+    pub fn new_env_var_equals<T, V>(name: T, value: V) -> Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        Self::EnvVar {
+            not: false,
+            name: name.into(),
+            equals: Some(value.into()),
+        }
+    }
+
+    /// Creates a new `Wait` that runs `program` with `args` on every check,
+    /// completing when it exits with `expected_code`. For example, to wait for
+    /// PostgreSQL to accept connections:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let pg_ready = Wait::new_command("pg_isready", ["-h", "localhost"], 0);
+    /// ```
+    ///
+    /// The command's stdin, stdout, and stderr are all null. If it can't be run
+    /// at all (eg, `program` doesn't exist) or is killed by a signal, the
+    /// condition isn't met, whether or not it's negated.
+    ///
+    /// When negated, this completes when the command exits with any other code.
+    pub fn new_command<P, I, A>(program: P, args: I, expected_code: i32) -> Self
+    where
+        P: Into<String>,
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        Self::Command {
+            not: false,
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            expected_code,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when `f` returns true.
+    ///
+    /// When negated, this completes when `f` returns false.
+    pub fn new_custom(f: fn() -> bool) -> Self {
+        Self::Custom { f, not: false }
+    }
+
+    /// Creates a new `Wait` that completes when the process with ID `pid` has
+    /// exited. This is checked with `kill(pid, 0)` on Unix (so a zombie process
+    /// that hasn't been reaped still counts as running) and with
+    /// `GetExitCodeProcess` on Windows.
+    ///
+    /// If we don't have permission to query the process, it's treated as having
+    /// exited.
+    ///
+    /// When negated, this completes when the process is running.
+    #[cfg(any(unix, windows))]
+    pub fn new_pid(pid: u32) -> Self {
+        Self::Pid { not: false, pid }
+    }
+
+    /// Creates a new `Wait` that completes when a running process's executable
+    /// is named `name`, eg, `"postgres"`. This must match the executable's file
+    /// name exactly, not a substring of it or its path.
+    ///
+    /// On Linux, this matches either the process's `comm` or the file name of
+    /// its `argv[0]`. On Windows, matching is case-insensitive and the `.exe`
+    /// extension is optional. Other platforms require the `sysinfo` feature.
+    ///
+    /// When negated, this completes when no such process is running.
+    #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+    pub fn new_process_named<T>(name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self::ProcessNamed {
+            not: false,
+            name: name.into(),
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the closure `f` returns true.
+    /// This is like [Self::new_custom], but `f` can capture its environment:
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering},
+    ///     Arc,
+    /// };
+    /// use waitforit::Wait;
+    ///
+    /// let ready = Arc::new(AtomicUsize::new(0));
+    /// let r = ready.clone();
+    /// let w = Wait::new_custom_closure(move || r.load(Ordering::SeqCst) > 0);
+    ///
+    /// assert!(!w.condition_met());
+    /// ready.store(1, Ordering::SeqCst);
+    /// assert!(w.condition_met());
+    /// ```
+    ///
+    /// When negated, this completes when `f` returns false.
+    pub fn new_custom_closure<F>(f: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        Self::CustomClosure {
+            f: CustomFn(Arc::new(f)),
+            not: false,
+        }
+    }
+
+    /// Creates a new `Wait` that completes when the closure `f` returns true.
+    /// This is like [Self::new_custom_closure], but `f` can mutate the state it
+    /// captures, such as to only succeed after three consecutive successes:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// fn probe() -> bool {
+    ///     true
+    /// }
+    ///
+    /// let mut successes = 0;
+    /// let w = Wait::new_custom_stateful(move || {
+    ///     successes = if probe() { successes + 1 } else { 0 };
+    ///     successes >= 3
+    /// });
+    /// w.wait(Duration::from_millis(10));
+    /// ```
+    ///
+    /// `f` is kept in a [Mutex], which is locked while it's called, so it's never
+    /// called by two threads at once. Clones of this `Wait` share `f` and its
+    /// state. Because of the lock, `f` mustn't check this `Wait` (or a clone of
+    /// it) itself, which would deadlock. If `f` panics, later checks carry on
+    /// with whatever state it left behind.
+    ///
+    /// When negated, this completes when `f` returns false.
+    pub fn new_custom_stateful<F>(f: F) -> Self
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        Self::CustomStateful {
+            f: CustomFnMut(Arc::new(Mutex::new(f))),
+            not: false,
+        }
+    }
+
+    /// Creates a new `Wait` that checks `inner` at most once per `interval`,
+    /// reusing its last result in between. This lets one condition in a [Waits]
+    /// be polled less often than the rest, such as a remote service alongside a
+    /// local file:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let db_up = Wait::new_tcp_connect("db.example.com:5432");
+    /// let db_up = Wait::new_throttled(db_up, Duration::from_secs(5));
+    /// let ready = db_up | Wait::new_file_exists("ready.flag");
+    /// ```
+    ///
+    /// `inner` is always checked the first time. After that, it's checked on the
+    /// first poll at least `interval` after the previous check, so the actual
+    /// cadence is `interval` rounded up to the polling interval.
+    ///
+    /// When negated, this completes when `inner` isn't met.
+    pub fn new_throttled(inner: Wait, interval: Duration) -> Self {
+        Self::Throttled {
+            not: false,
+            inner: Box::new(inner),
+            interval,
+            last_check: SyncCell::new(None),
+            last_met: SyncCell::new(false),
+        }
+    }
+
+    /// Forgets anything this condition has recorded from previous checks, such
+    /// as the last-seen size of a [Self::new_file_size], so that it can be reused
+    /// for a fresh wait. For example, to wait for a file to change, act on it,
+    /// then wait for it to change again:
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let changed = Wait::new_file_size("inbox.csv");
+    /// changed.wait(Duration::from_secs(1));
+    /// // ... process inbox.csv ...
+    /// changed.reset();
+    /// changed.wait(Duration::from_secs(1));
+    /// ```
+    ///
+    /// This has no effect on conditions that don't record anything. Note that
+    /// an elapsed-time condition's start is fixed when it's created, and that
+    /// the state of a [Self::new_custom_stateful] closure is its own.
+    pub fn reset(&self) {
+        match self {
+            #[cfg(feature = "cron")]
+            Wait::Schedule { next, .. } => next.set(None),
+            Wait::Update { last_update, .. } => last_update.set(None),
+            Wait::FileSize { size_bytes, .. } => size_bytes.set(None),
+            Wait::FileContains { offset, .. } => offset.set(0),
+            Wait::StdinLine { lines, .. } => lines.reset(),
+            #[cfg(all(unix, feature = "signal"))]
+            Wait::Signal { received, .. } => received.reset(),
+            Wait::FileHashStable { last_hash, .. } => last_hash.set(None),
+            #[cfg(feature = "chrono")]
+            Wait::TimeOfDay { last_fired_day, .. } => last_fired_day.set(None),
+            Wait::Throttled {
+                inner,
+                last_check,
+                last_met,
+                ..
+            } => {
+                inner.reset();
+                last_check.set(None);
+                last_met.set(false);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether this condition is negated, ie, whether its `not` flag is set.
+    pub fn is_negated(&self) -> bool {
+        match self {
+            Wait::Elapsed { not, .. } => *not,
+            Wait::Exists { not, .. } => *not,
+            Wait::Until { not, .. } => *not,
+            #[cfg(feature = "cron")]
+            Wait::Schedule { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpGet { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpGetRange { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpPost { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpBody { not, .. } => *not,
+            #[cfg(all(feature = "http", feature = "regex"))]
+            Wait::HttpBodyRegex { not, .. } => *not,
+            Wait::TcpHost { not, .. } => *not,
+            Wait::DnsResolves { not, .. } => *not,
+            Wait::PortFree { not, .. } => *not,
+            Wait::UdpHost { not, .. } => *not,
+            Wait::DirExists { not, .. } => *not,
+            Wait::DirNonEmpty { not, .. } => *not,
+            Wait::DirFileCount { not, .. } => *not,
+            #[cfg(feature = "glob")]
+            Wait::GlobExists { not, .. } => *not,
+            Wait::Update { not, .. } => *not,
+            Wait::UpdateSince { not, .. } => *not,
+            Wait::FileSize { not, .. } => *not,
+            Wait::FileSizeAtLeast { not, .. } => *not,
+            Wait::FileContains { not, .. } => *not,
+            Wait::FileHashStable { not, .. } => *not,
+            Wait::FileUnlocked { not, .. } => *not,
+            #[cfg(any(unix, windows))]
+            Wait::DiskFree { not, .. } => *not,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::MemoryFree { not, .. } => *not,
+            #[cfg(unix)]
+            Wait::LoadBelow { not, .. } => *not,
+            Wait::TcpBanner { not, .. } => *not,
+            #[cfg(unix)]
+            Wait::UnixSocket { not, .. } => *not,
+            #[cfg(unix)]
+            Wait::FifoReady { not, .. } => *not,
+            Wait::Always { not, .. } => *not,
+            Wait::Never { not, .. } => *not,
+            #[cfg(feature = "tls")]
+            Wait::TlsCertValid { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpHeader { not, .. } => *not,
+            #[cfg(feature = "json")]
+            Wait::HttpJson { not, .. } => *not,
+            Wait::ModifiedSince { not, .. } => *not,
+            Wait::StdinLine { not, .. } => *not,
+            #[cfg(all(unix, feature = "signal"))]
+            Wait::Signal { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpLatency { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpHead { not, .. } => *not,
+            Wait::FileEmpty { not, .. } => *not,
+            #[cfg(unix)]
+            Wait::FileMode { not, .. } => *not,
+            #[cfg(feature = "http")]
+            Wait::HttpMulti { not, .. } => *not,
+            #[cfg(feature = "chrono")]
+            Wait::TimeOfDay { not, .. } => *not,
+            Wait::EnvVar { not, .. } => *not,
+            Wait::Command { not, .. } => *not,
+            Wait::Custom { not, .. } => *not,
+            Wait::CustomClosure { not, .. } => *not,
+            Wait::CustomStateful { not, .. } => *not,
+            #[cfg(any(unix, windows))]
+            Wait::Pid { not, .. } => *not,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::ProcessNamed { not, .. } => *not,
+            Wait::Throttled { not, .. } => *not,
+        }
+    }
+
+    /// Roughly how costly it is to check this condition, for deciding what to
+    /// check first (see [Waits::optimized](crate::Waits::optimized)). Custom
+    /// conditions are assumed to be cheap.
+    pub fn estimated_cost(&self) -> Cost {
+        match self {
+            #[cfg(feature = "http")]
+            Wait::HttpGet { .. }
+            | Wait::HttpGetRange { .. }
+            | Wait::HttpPost { .. }
+            | Wait::HttpBody { .. } => Cost::Network,
+            #[cfg(all(feature = "http", feature = "regex"))]
+            Wait::HttpBodyRegex { .. } => Cost::Network,
+            Wait::TcpHost { .. }
+            | Wait::TcpBanner { .. }
+            | Wait::DnsResolves { .. }
+            | Wait::UdpHost { .. } => Cost::Network,
+            Wait::Command { .. } => Cost::Subprocess,
+            Wait::Throttled { inner, .. } => inner.estimated_cost(),
+            Wait::FileUnlocked { .. } => Cost::Local,
+            #[cfg(any(unix, windows))]
+            Wait::DiskFree { .. } => Cost::Local,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::MemoryFree { .. } => Cost::Local,
+            #[cfg(unix)]
+            Wait::LoadBelow { .. } => Cost::Local,
+            #[cfg(unix)]
+            Wait::UnixSocket { .. } => Cost::Local,
+            #[cfg(unix)]
+            Wait::FifoReady { .. } => Cost::Local,
+            Wait::Always { .. } => Cost::Local,
+            Wait::Never { .. } => Cost::Local,
+            #[cfg(feature = "tls")]
+            Wait::TlsCertValid { .. } => Cost::Network,
+            #[cfg(feature = "http")]
+            Wait::HttpHeader { .. } => Cost::Network,
+            #[cfg(feature = "json")]
+            Wait::HttpJson { .. } => Cost::Network,
+            Wait::ModifiedSince { .. } => Cost::Local,
+            Wait::StdinLine { .. } => Cost::Local,
+            #[cfg(all(unix, feature = "signal"))]
+            Wait::Signal { .. } => Cost::Local,
+            #[cfg(feature = "http")]
+            Wait::HttpLatency { .. } => Cost::Network,
+            #[cfg(feature = "http")]
+            Wait::HttpHead { .. } => Cost::Network,
+            Wait::FileEmpty { .. } => Cost::Local,
+            #[cfg(unix)]
+            Wait::FileMode { .. } => Cost::Local,
+            #[cfg(feature = "http")]
+            Wait::HttpMulti { .. } => Cost::Network,
+            #[cfg(feature = "chrono")]
+            Wait::TimeOfDay { .. } => Cost::Local,
+            Wait::Elapsed { .. }
+            | Wait::Exists { .. }
+            | Wait::Until { .. }
+            | Wait::PortFree { .. }
+            | Wait::DirExists { .. }
+            | Wait::DirNonEmpty { .. }
+            | Wait::DirFileCount { .. }
+            | Wait::Update { .. }
+            | Wait::UpdateSince { .. }
+            | Wait::FileSize { .. }
+            | Wait::FileSizeAtLeast { .. }
+            | Wait::FileContains { .. }
+            | Wait::FileHashStable { .. }
+            | Wait::EnvVar { .. }
+            | Wait::Custom { .. }
+            | Wait::CustomClosure { .. }
+            | Wait::CustomStateful { .. } => Cost::Local,
+            #[cfg(feature = "cron")]
+            Wait::Schedule { .. } => Cost::Local,
+            #[cfg(feature = "glob")]
+            Wait::GlobExists { .. } => Cost::Local,
+            #[cfg(any(unix, windows))]
+            Wait::Pid { .. } => Cost::Local,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::ProcessNamed { .. } => Cost::Local,
+        }
+    }
+
+    //
+
+    /// Checks whether this condition is met.
+    ///
+    /// This is non-blocking, but depending on the variant may have some associated
+    /// delay (eg, an HTTP GET incurs TCP and possibly TLS handshake latency).
+    ///
+    /// With the `log` feature, every check is logged at the debug level, so the
+    /// checks of a [Waits](crate::Waits) show which of its `Wait`s was met. With
+    /// the `tracing` feature, every check emits a trace-level event instead.
+    pub fn condition_met(&self) -> bool {
+        let met = self.check();
+        #[cfg(feature = "log")]
+        log::debug!("{self}: {}", if met { "met" } else { "not met" });
+        #[cfg(feature = "tracing")]
+        tracing::trace!(condition = %self, met);
+        met
+    }
+
+    /// Checks this condition, as [Self::condition_met] does, without logging.
+    pub(crate) fn check(&self) -> bool {
+        match self {
+            Wait::Elapsed { end_instant, not } => elapsed(*end_instant, Instant::now()) ^ not,
+            Wait::Exists { not: true, path } => !Path::new(path).exists(),
+            Wait::Exists { not: false, path } => Path::new(path).exists(),
+            Wait::Until { not, when } => (SystemTime::now() >= *when) ^ not,
+            #[cfg(feature = "cron")]
+            Wait::Schedule { not, cron, next } => {
+                let next = match next.get() {
+                    Some(next) => Some(next),
+                    None => {
+                        let upcoming = next_scheduled(cron);
+                        next.set(upcoming);
+                        upcoming
+                    }
+                };
+
+                match next {
+                    Some(next) => (SystemTime::now() >= next) ^ not,
+                    // There's nothing scheduled, so it'll never happen
+                    None => *not,
+                }
+            }
+            #[cfg(feature = "http")]
+            Wait::HttpGet {
+                not,
+                url,
+                status,
+                options,
+            } => match http::status(http::request("GET", url, options).call()) {
+                Some(s) => (*status == s) ^ not,
+                // No response at all, so it's certainly not returning `status`
+                None => *not,
+            },
+            #[cfg(feature = "http")]
+            Wait::HttpGetRange {
+                not,
+                url,
+                statuses,
+                options,
+            } => match http::status(http::request("GET", url, options).call()) {
+                Some(s) => statuses.contains(&s) ^ not,
+                None => *not,
+            },
+            #[cfg(feature = "http")]
+            Wait::HttpPost {
+                not,
+                url,
+                body,
+                content_type,
+                status,
+                options,
+            } => {
+                let result = http::request("POST", url, options)
+                    .set("Content-Type", content_type)
+                    .send_bytes(body);
+                match http::status(result) {
+                    Some(s) => (*status == s) ^ not,
+                    None => *not,
+                }
+            }
+            #[cfg(feature = "http")]
+            Wait::HttpBody {
+                not,
+                url,
+                contains,
+                options,
+            } => match http::body(http::request("GET", url, options).call()) {
+                Some(body) => body.contains(contains.as_str()) ^ not,
+                None => *not,
+            },
+            #[cfg(all(feature = "http", feature = "regex"))]
+            Wait::HttpBodyRegex {
+                not,
+                url,
+                pattern,
+                options,
+            } => match http::body(http::request("GET", url, options).call()) {
+                Some(body) => pattern.is_match(&body) ^ not,
+                None => *not,
+            },
+            Wait::TcpHost { not, host, timeout } => tcp_connects(host, *timeout) ^ not,
+            Wait::DnsResolves { not, host } => matches!(dns_resolves(host), Ok(true)) ^ not,
+            Wait::PortFree { not, addr } => TcpListener::bind(addr.as_str()).is_ok() ^ not,
+            Wait::UdpHost {
+                not,
+                host,
+                payload,
+                expect_reply,
+                timeout,
+            } => udp_reachable(host, payload, *expect_reply, *timeout).is_ok() ^ not,
+            Wait::DirExists { not, path } => path.is_dir() ^ not,
+            Wait::DirFileCount {
+                not,
+                path,
+                min_count,
+                extension,
+                files_only,
+            } => (dir_file_count(path, extension.as_deref(), *files_only) >= *min_count) ^ not,
+            #[cfg(feature = "glob")]
+            Wait::GlobExists { not, pattern } => {
+                let matched = match glob::glob(pattern) {
+                    Ok(mut paths) => paths.any(|p| p.is_ok()),
+                    Err(_) => false,
+                };
+                matched ^ not
+            }
+            Wait::DirNonEmpty { not, path } => {
+                let non_empty = match std::fs::read_dir(path) {
+                    Ok(mut entries) => entries.next().is_some_and(|e| e.is_ok()),
+                    Err(_) => false,
+                };
+                non_empty ^ not
+            }
+            Wait::Update {
+                not,
+                path,
+                last_update,
+                require_exists,
+            } => {
+                // "Update" checks that the file has (not) been updated in the last 'trigger_duration'
+                last_update.update(|last_update| {
+                    let current_modified = match get_modified_time(path) {
+                        Some(systime) => systime,
+                        None if *require_exists => {
+                            // Start over if the file reappears
+                            *last_update = None;
+                            return false;
+                        }
+                        None => return true, // Can't get the modified time, so we'll assume the condition is met.
+                    };
+
+                    match *last_update {
+                        Some(last_updated) => {
+                            let is_updated = last_updated != current_modified;
+
+                            if *not {
+                                // We want to trigger when the file *isn't* updating.
+                                if is_updated {
+                                    // Shouldn't trigger yet, but we should update the last known modified date
+                                    *last_update = Some(current_modified);
+                                    false
+                                } else {
+                                    // File hasn't updated, so we should trigger
+                                    true
+                                }
+                            } else {
+                                // Since not==false: iff the file is updated, we trigger.
+                                // Triggering on an updated mod time doesn't ever need to update the value
+                                is_updated
+                            }
+                        }
+                        None => {
+                            // Haven't tracked the time yet. We'll hang onto it now for the next iteration
+                            *last_update = Some(current_modified);
+                            false
+                        }
+                    }
+                })
+            }
+            Wait::UpdateSince {
+                not,
+                path,
+                trigger_duration,
+            } => {
+                // "Update" checks that the file has (not) been updated in the last 'trigger_duration'
+                let last_updated = match get_modified_time(path) {
+                    Some(up) => up,
+                    None => return true, // Can't get the modified time, so we'll assume the condition is met.
+                };
+
+                let since_last_update = match SystemTime::now().duration_since(last_updated) {
+                    Ok(d) => d,
+                    Err(_) => return true, // can't calculate the duration, so we'll assume the condition is met
+                };
+
+                let is_recently_updated = since_last_update < *trigger_duration;
+
+                // The condition is met if:
+                // 1. we're looking for a recent update and it was recently updated, or
+                // 2. it's not recently updated and we're not looking for it, which means
+                // condition_met := is_recently_updated XOR not_negating
+                is_recently_updated ^ not
+            }
+
+            Wait::FileSize {
+                not,
+                path,
+                size_bytes: bytes,
+                change,
+                require_exists,
+            } => bytes.update(|bytes| {
+                match (*bytes, get_file_size(path)) {
+                    // The file must exist, so the condition isn't met. Start over if it reappears
+                    (_, None) if *require_exists => {
+                        *bytes = None;
+                        false
+                    }
+                    // Can't get the file size. This is probably due to file non-existence,
+                    // so we'll assume the condition is met
+                    (_, None) => true,
+                    // Size changed as expected when not negating, or didn't when
+                    // negating -- condition is met
+                    (Some(prev), Some(curr)) if change.matches(prev, curr) ^ not => true,
+                    // First time or subsequent with changing values - save the (new) size and try again
+                    (_, curr) => {
+                        *bytes = curr;
+                        false
+                    }
+                }
+            }),
+
+            Wait::FileSizeAtLeast {
+                not,
+                path,
+                min_bytes,
+            } => get_file_size(path).is_some_and(|size| size >= *min_bytes) ^ not,
+            Wait::FileContains {
+                not,
+                path,
+                needle,
+                offset,
+            } => {
+                matches!(
+                    offset.update(|offset| file_contains(path, needle, offset)),
+                    Ok(true)
+                ) ^ not
+            }
+            Wait::FileHashStable {
+                not,
+                path,
+                last_hash,
+            } => match get_file_hash(path) {
+                Some(curr) => match last_hash.replace(Some(curr)) {
+                    Some(prev) => (prev == curr) ^ not,
+                    // Haven't hashed the file yet. We'll compare against it next time
+                    None => false,
+                },
+                None => {
+                    last_hash.set(None);
+                    false
+                }
+            },
+            Wait::FileUnlocked { not, path } => file_unlocked(path) ^ not,
+            #[cfg(any(unix, windows))]
+            Wait::DiskFree {
+                not,
+                path,
+                min_bytes,
+            } => match system::available_disk_space(path) {
+                Some(available) => (available >= *min_bytes) ^ not,
+                None => false,
+            },
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::MemoryFree { not, min_bytes } => match system::available_memory() {
+                Some(available) => (available >= *min_bytes) ^ not,
+                None => false,
+            },
+            #[cfg(unix)]
+            Wait::LoadBelow {
+                not,
+                threshold,
+                window,
+            } => match system::load_averages() {
+                Some(averages) => (averages[*window as usize] < *threshold) ^ not,
+                None => false,
+            },
+            Wait::TcpBanner {
+                not,
+                host,
+                expect,
+                read_timeout,
+            } => {
+                tcp_banner(host, *read_timeout)
+                    .is_some_and(|banner| banner.starts_with(expect.as_bytes()))
+                    ^ not
+            }
+            #[cfg(unix)]
+            Wait::UnixSocket { not, path } => {
+                std::os::unix::net::UnixStream::connect(path).is_ok() ^ not
+            }
+            #[cfg(unix)]
+            Wait::FifoReady { not, path } => fifo_ready(path) ^ not,
+            Wait::Always { not } => !not,
+            Wait::Never { not } => *not,
+            Wait::CustomStateful { f, not } => f.call() ^ not,
+            #[cfg(feature = "tls")]
+            Wait::TlsCertValid {
+                not,
+                host,
+                min_remaining,
+                timeout,
+            } => match tls::cert_remaining(host, *timeout) {
+                Some(remaining) => (remaining > *min_remaining) ^ not,
+                None => false,
+            },
+            #[cfg(feature = "http")]
+            Wait::HttpHeader {
+                not,
+                url,
+                header,
+                equals,
+                options,
+            } => match http::header(http::request("GET", url, options).call(), header) {
+                Some(value) => http::header_matches(value.as_deref(), equals.as_deref()) ^ not,
+                None => *not,
+            },
+            #[cfg(feature = "json")]
+            Wait::HttpJson {
+                not,
+                url,
+                pointer,
+                equals,
+                options,
+            } => match http::body(http::request("GET", url, options).call()) {
+                Some(body) => http::json_matches(&body, pointer, equals, *not),
+                None => *not,
+            },
+            Wait::ModifiedSince {
+                not,
+                path,
+                reference,
+            } => match get_modified_time(path) {
+                Some(modified) => (modified > *reference) ^ not,
+                None => false,
+            },
+            Wait::StdinLine { not, needle, lines } => lines.contains(needle) ^ not,
+            #[cfg(all(unix, feature = "signal"))]
+            Wait::Signal { not, received, .. } => received.get() ^ not,
+            #[cfg(feature = "http")]
+            Wait::HttpLatency {
+                not,
+                url,
+                max,
+                options,
+            } => {
+                let start = Instant::now();
+                let status = http::status(http::request("GET", url, options).call());
+                http::within_latency(status, start.elapsed(), *max) ^ not
+            }
+            #[cfg(feature = "http")]
+            Wait::HttpHead {
+                not,
+                url,
+                status,
+                options,
+            } => match http::status(http::request("HEAD", url, options).call()) {
+                Some(s) => (*status == s) ^ not,
+                None => *not,
+            },
+            Wait::FileEmpty { not, path } => match get_file_size(path) {
+                Some(size) => (size == 0) ^ not,
+                None => false,
+            },
+            #[cfg(unix)]
+            Wait::FileMode {
+                not,
+                path,
+                mask,
+                expect,
+            } => {
+                use std::os::unix::fs::PermissionsExt;
+                match path.metadata() {
+                    Ok(meta) => (meta.permissions().mode() & mask == *expect) ^ not,
+                    Err(_) => false,
+                }
+            }
+            #[cfg(feature = "http")]
+            Wait::HttpMulti {
+                not,
+                urls,
+                status,
+                mode,
+                options,
+            } => http::multi_status(urls, *status, *mode, options) ^ not,
+            #[cfg(feature = "chrono")]
+            Wait::TimeOfDay {
+                not,
+                hour,
+                minute,
+                last_fired_day,
+            } => {
+                let now = chrono::Local::now().naive_local();
+                time_of_day_reached(now, *hour, *minute, last_fired_day) ^ not
+            }
+            Wait::EnvVar { not, name, equals } => {
+                let met = match (std::env::var(name), equals) {
+                    (Ok(value), Some(equals)) => value == *equals,
+                    (Ok(_), None) => true,
+                    (Err(_), _) => false,
+                };
+                met ^ not
+            }
+            Wait::Command {
+                not,
+                program,
+                args,
+                expected_code,
+            } => match process::exit_code(program, args) {
+                Some(code) => (code == *expected_code) ^ not,
+                None => false,
+            },
+            Wait::Custom { f, not } => {
+                if *not {
+                    !(f)()
+                } else {
+                    (f)()
+                }
+            }
+            Wait::CustomClosure { f, not } => f.call() ^ not,
+            #[cfg(any(unix, windows))]
+            Wait::Pid { not, pid } => process::pid_exited(*pid) ^ not,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::ProcessNamed { not, name } => process::process_named(name) ^ not,
+            Wait::Throttled {
+                not,
+                inner,
+                interval,
+                last_check,
+                last_met,
+            } => {
+                let met = last_check.update(|last_check| {
+                    if last_check.is_none_or(|last| last.elapsed() >= *interval) {
+                        *last_check = Some(Instant::now());
+                        last_met.set(inner.condition_met());
+                    }
+                    last_met.get()
+                });
+                met ^ not
+            }
+        }
+    }
+
+    /// Wait for the completion of this condition. This will block the thread.
+    pub fn wait(&self, interval: Duration) {
+        poll::wait(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        );
+    }
+
+    /// Wait for the completion of this condition, checking at intervals decided by
+    /// `schedule` (eg, [ExponentialBackoff](crate::ExponentialBackoff)). This
+    /// will block the thread.
+    pub fn wait_with_schedule(&self, schedule: &dyn PollSchedule) {
+        poll::wait_with_schedule(
+            poll::instrumented(self, None, || self.condition_met()),
+            schedule,
+        );
+    }
+
+    /// Wait for the completion of this condition, checking every `interval` give or
+    /// take a random amount of up to `jitter`. This spreads out checks from many
+    /// processes that start at the same time, though it works best with a
+    /// backoff (see [Jittered](crate::Jittered)). This will block the thread.
+    #[cfg(feature = "rand")]
+    pub fn wait_jittered(&self, interval: Duration, jitter: Duration) {
+        let schedule = crate::Jittered {
+            schedule: crate::FixedInterval(interval),
+            jitter,
+        };
+        self.wait_with_schedule(&schedule);
+    }
+
+    /// Wait for the completion of this condition, checking it at most
+    /// `max_attempts` times, every `interval`. This will block the thread.
+    ///
+    /// Returns `true` if the condition was met and `false` if every attempt was
+    /// used up first. This is useful when each check is costly (eg, running a
+    /// command) and the number of checks matters more than the time taken. With
+    /// zero `max_attempts`, this checks nothing and returns `false`.
+    pub fn wait_max_attempts(&self, interval: Duration, max_attempts: u32) -> bool {
+        poll::wait_max_attempts(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+            max_attempts,
+        )
+    }
+
+    /// Wait for the completion of this condition or for `cancel` to be set (eg,
+    /// by another thread during shutdown), checking every `interval`. This will
+    /// block the thread.
+    ///
+    /// Returns `true` if the condition was met and `false` if the wait was
+    /// cancelled. `cancel` is checked before every check of the condition and
+    /// frequently while sleeping, so cancellation is prompt even with a long
+    /// `interval`.
+    pub fn wait_cancellable(&self, interval: Duration, cancel: &Arc<AtomicBool>) -> bool {
+        poll::wait_cancellable(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+            cancel,
+        )
+    }
+
+    /// Wait for the completion of this condition or for the user to press Ctrl-C,
+    /// checking every `interval`. This will block the thread.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use waitforit::{Wait, WaitResult};
+    ///
+    /// let w = Wait::new_tcp_connect("localhost:5432");
+    /// if w.wait_interruptible(Duration::from_secs(1)) == WaitResult::Interrupted {
+    ///     eprintln!("Gave up waiting for the database");
+    ///     std::process::exit(130);
+    /// }
+    /// ```
+    ///
+    /// The first interruptible wait installs a Ctrl-C handler with the
+    /// [`ctrlc`](https://docs.rs/ctrlc/) crate, which stays installed, so from
+    /// then on Ctrl-C no longer terminates the process; it only interrupts
+    /// interruptible waits, including any running at the same time on other
+    /// threads, and presses outside of them are ignored. Like
+    /// [Self::wait_cancellable], an interruption is noticed promptly even with a
+    /// long `interval`. If the program has already installed its own handler
+    /// with `ctrlc`, this can't install one, so it can't be interrupted.
+    #[cfg(feature = "ctrlc")]
+    pub fn wait_interruptible(&self, interval: Duration) -> crate::WaitResult {
+        poll::wait_interruptible(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        )
+    }
+
+    /// Wait for the completion of this condition, checking every `interval` and
+    /// calling `on_poll` after each check with the attempt number (counting from
+    /// zero) and whether the condition was met. This will block the thread.
+    ///
+    /// The check that meets the condition is reported too, before this returns.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let w = Wait::new_elapsed_from_duration(Duration::from_millis(20));
+    /// w.wait_with_callback(Duration::from_millis(10), |attempt, met| {
+    ///     if !met {
+    ///         println!("attempt {attempt}: still waiting");
+    ///     }
+    /// });
+    /// ```
+    pub fn wait_with_callback<F>(&self, interval: Duration, mut on_poll: F)
+    where
+        F: FnMut(u32, bool),
+    {
+        let mut attempt = 0;
+        let condition = || {
+            let met = self.condition_met();
+            on_poll(attempt, met);
+            attempt += 1;
+            met
+        };
+        poll::wait(
+            poll::instrumented(self, Some(interval), condition),
+            interval,
+        );
+    }
+
+    /// Wait for the completion of this condition on a new thread, checking every
+    /// `interval`, without blocking this one. The returned handle's thread
+    /// finishes, with `true`, once the condition is met; use
+    /// [JoinHandle::is_finished] to check on it without blocking, or
+    /// [JoinHandle::join] to block until then.
+    ///
+    /// Every `Wait` is [Send], so this is always possible. The runtime state of
+    /// conditions like [Wait::FileSize] moves to the new thread along with `self`.
+    pub fn spawn(self, interval: Duration) -> JoinHandle<bool> {
+        std::thread::spawn(move || {
+            self.wait(interval);
+            true
+        })
+    }
+
+    /// Wait for the completion of this condition, returning how long it took,
+    /// from the start of the first check to the end of the one that was
+    /// satisfied. This will block the thread.
+    pub fn wait_elapsed(&self, interval: Duration) -> Duration {
+        poll::wait(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        )
+    }
+
+    /// Wait for the completion of this condition, returning how many checks it
+    /// took, counting the one that was satisfied (so a condition that's already
+    /// met returns 1). This will block the thread. Together with
+    /// [Self::wait_elapsed], this helps to choose an `interval`.
+    ///
+    /// The count saturates at [u32::MAX], which a zero `interval` could reach.
+    pub fn wait_counted(&self, interval: Duration) -> u32 {
+        poll::wait_counted(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        )
+    }
+
+    /// Wait for the completion of this condition, giving up once `timeout` has
+    /// elapsed. This will block the thread.
+    ///
+    /// Returns `true` if the condition was met and `false` if the timeout elapsed
+    /// first. The condition is checked at least once, so a zero `timeout` does a
+    /// single check and returns immediately.
+    pub fn wait_timeout(&self, interval: Duration, timeout: Duration) -> bool {
+        poll::wait_timeout(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+            timeout,
+        )
+    }
+}
+
+impl std::ops::Not for Wait {
+    type Output = Self;
+
+    fn not(mut self) -> Self::Output {
+        let not = match &mut self {
+            Wait::Elapsed { not, .. } => not,
+            Wait::Exists { not, .. } => not,
+            Wait::Until { not, .. } => not,
+            #[cfg(feature = "cron")]
+            Wait::Schedule { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpGet { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpGetRange { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpPost { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpBody { not, .. } => not,
+            #[cfg(all(feature = "http", feature = "regex"))]
+            Wait::HttpBodyRegex { not, .. } => not,
+            Wait::TcpHost { not, .. } => not,
+            Wait::DnsResolves { not, .. } => not,
+            Wait::PortFree { not, .. } => not,
+            Wait::UdpHost { not, .. } => not,
+            Wait::DirExists { not, .. } => not,
+            Wait::DirNonEmpty { not, .. } => not,
+            Wait::DirFileCount { not, .. } => not,
+            #[cfg(feature = "glob")]
+            Wait::GlobExists { not, .. } => not,
+            Wait::Update { not, .. } => not,
+            Wait::UpdateSince { not, .. } => not,
+            Wait::FileSize { not, .. } => not,
+            Wait::FileSizeAtLeast { not, .. } => not,
+            Wait::FileContains { not, .. } => not,
+            Wait::FileHashStable { not, .. } => not,
+            Wait::FileUnlocked { not, .. } => not,
+            #[cfg(any(unix, windows))]
+            Wait::DiskFree { not, .. } => not,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::MemoryFree { not, .. } => not,
+            #[cfg(unix)]
+            Wait::LoadBelow { not, .. } => not,
+            Wait::TcpBanner { not, .. } => not,
+            #[cfg(unix)]
+            Wait::UnixSocket { not, .. } => not,
+            #[cfg(unix)]
+            Wait::FifoReady { not, .. } => not,
+            Wait::Always { not, .. } => not,
+            Wait::Never { not, .. } => not,
+            #[cfg(feature = "tls")]
+            Wait::TlsCertValid { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpHeader { not, .. } => not,
+            #[cfg(feature = "json")]
+            Wait::HttpJson { not, .. } => not,
+            Wait::ModifiedSince { not, .. } => not,
+            Wait::StdinLine { not, .. } => not,
+            #[cfg(all(unix, feature = "signal"))]
+            Wait::Signal { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpLatency { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpHead { not, .. } => not,
+            Wait::FileEmpty { not, .. } => not,
+            #[cfg(unix)]
+            Wait::FileMode { not, .. } => not,
+            #[cfg(feature = "http")]
+            Wait::HttpMulti { not, .. } => not,
+            #[cfg(feature = "chrono")]
+            Wait::TimeOfDay { not, .. } => not,
+            Wait::EnvVar { not, .. } => not,
+            Wait::Command { not, .. } => not,
+            Wait::Custom { not, .. } => not,
+            Wait::CustomClosure { not, .. } => not,
+            Wait::CustomStateful { not, .. } => not,
+            #[cfg(any(unix, windows))]
+            Wait::Pid { not, .. } => not,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::ProcessNamed { not, .. } => not,
+            Wait::Throttled { not, .. } => not,
+        };
+
+        *not = !*not;
+
+        self
+
+        /*
+        match self {
+            Wait::Elapsed { end_instant, not } => Wait::Elapsed {
+                end_instant,
+                not: !not,
+            },
+            Wait::Exists { not, path } => Wait::Exists { not: !not, path },
+            Wait::HttpGet { not, url, status } => Wait::HttpGet {
+                not: !not,
+                url,
+                status,
+            },
+            Wait::TcpHost { not, host } => Wait::TcpHost { not: !not, host },
+            Wait::Update {
+                not,
+                path,
+                last_update,
+            } => Wait::Update {
+                not: !not,
+                path,
+                last_update,
+            },
+            Wait::UpdateSince {
+                not,
+                path,
+                trigger_duration,
+            } => Wait::UpdateSince {
+                not: !not,
+                path,
+                trigger_duration,
+            },
+            Wait::FileSize {
+                not,
+                path,
+                size_bytes,
+            } => Wait::FileSize {
+                not: !not,
+                path,
+                size_bytes,
+            },
+            Wait::Custom { f, not } => Wait::Custom { f, not: !not },
+        }
+        */
+    }
+}
+
+/// Renders this condition as `kind(arguments)`, prefixed with `NOT` when negated,
+/// eg, `NOT exists("foo.txt")`. Where [Waits::parse] has a matching `kind` (eg,
+/// `exists` and `tcp`), the same name is used.
+///
+/// `Elapsed` is shown as the time remaining, and `Until` as seconds since the
+/// Unix epoch. Runtime state (eg, the last seen modified time) isn't shown.
+impl std::fmt::Display for Wait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_negated() {
+            f.write_str("NOT ")?;
+        }
+
+        match self {
+            Wait::Elapsed { end_instant, .. } => {
+                let remaining = end_instant.saturating_duration_since(Instant::now());
+                write!(f, "elapsed({})", format_duration(remaining))
+            }
+            Wait::Until { when, .. } => match when.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(since) => write!(f, "until({})", since.as_secs()),
+                Err(e) => write!(f, "until(-{})", e.duration().as_secs()),
+            },
+            #[cfg(feature = "cron")]
+            Wait::Schedule { cron, .. } => write!(f, "schedule({cron:?})"),
+            Wait::Exists { path, .. } => write!(f, "exists({path:?})"),
+            Wait::DirExists { path, .. } => write!(f, "dir_exists({path:?})"),
+            Wait::DirNonEmpty { path, .. } => write!(f, "dir_non_empty({path:?})"),
+            Wait::DirFileCount {
+                path,
+                min_count,
+                extension,
+                files_only,
+                ..
+            } => {
+                write!(f, "dir_file_count({path:?}, {min_count}")?;
+                if let Some(extension) = extension {
+                    write!(f, ", extension = {extension:?}")?;
+                }
+                if *files_only {
+                    f.write_str(", files_only")?;
+                }
+                f.write_str(")")
+            }
+            #[cfg(feature = "glob")]
+            Wait::GlobExists { pattern, .. } => write!(f, "glob({pattern:?})"),
+            Wait::Update { path, .. } => write!(f, "updated({path:?})"),
+            Wait::UpdateSince {
+                path,
+                trigger_duration,
+                ..
+            } => write!(
+                f,
+                "updated_within({path:?}, {})",
+                format_duration(*trigger_duration)
+            ),
+            Wait::TcpHost { host, .. } => write!(f, "tcp({host:?})"),
+            Wait::DnsResolves { host, .. } => write!(f, "dns({host:?})"),
+            Wait::PortFree { addr, .. } => write!(f, "port_free({addr:?})"),
+            Wait::UdpHost { host, .. } => write!(f, "udp({host:?})"),
+            #[cfg(feature = "http")]
+            Wait::HttpGet { url, status, .. } => write!(f, "http({url:?}, {status})"),
+            #[cfg(feature = "http")]
+            Wait::HttpGetRange { url, statuses, .. } => write!(
+                f,
+                "http({url:?}, {}..={})",
+                statuses.start(),
+                statuses.end()
+            ),
+            #[cfg(feature = "http")]
+            Wait::HttpPost { url, status, .. } => write!(f, "http_post({url:?}, {status})"),
+            #[cfg(feature = "http")]
+            Wait::HttpBody { url, contains, .. } => write!(f, "http_body({url:?}, {contains:?})"),
+            #[cfg(all(feature = "http", feature = "regex"))]
+            Wait::HttpBodyRegex { url, pattern, .. } => {
+                write!(f, "http_body_regex({url:?}, {:?})", pattern.as_str())
+            }
+            Wait::FileSize { path, change, .. } => match change {
+                SizeChange::Any => write!(f, "file_size_changed({path:?})"),
+                SizeChange::Grow => write!(f, "file_size_grew({path:?})"),
+                SizeChange::Shrink => write!(f, "file_size_shrank({path:?})"),
+            },
+            Wait::FileSizeAtLeast {
+                path, min_bytes, ..
+            } => write!(f, "file_size_at_least({path:?}, {min_bytes})"),
+            Wait::FileContains { path, needle, .. } => {
+                write!(f, "file_contains({path:?}, {needle:?})")
+            }
+            Wait::FileHashStable { path, .. } => write!(f, "file_stable({path:?})"),
+            Wait::FileUnlocked { path, .. } => write!(f, "file_unlocked({path:?})"),
+            #[cfg(any(unix, windows))]
+            Wait::DiskFree {
+                path, min_bytes, ..
+            } => write!(f, "disk_free({path:?}, {min_bytes})"),
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::MemoryFree { min_bytes, .. } => write!(f, "memory_free({min_bytes})"),
+            #[cfg(unix)]
+            Wait::LoadBelow {
+                threshold, window, ..
+            } => write!(f, "load_below({threshold}, {window})"),
+            Wait::TcpBanner { host, expect, .. } => write!(f, "tcp_banner({host:?}, {expect:?})"),
+            #[cfg(unix)]
+            Wait::UnixSocket { path, .. } => write!(f, "unix_socket({path:?})"),
+            #[cfg(unix)]
+            Wait::FifoReady { path, .. } => write!(f, "fifo_ready({path:?})"),
+            Wait::Always { .. } => f.write_str("always()"),
+            Wait::Never { .. } => f.write_str("never()"),
+            #[cfg(feature = "tls")]
+            Wait::TlsCertValid {
+                host,
+                min_remaining,
+                ..
+            } => write!(f, "tls_cert({host:?}, {})", format_duration(*min_remaining)),
+            #[cfg(feature = "http")]
+            Wait::HttpHeader {
+                url,
+                header,
+                equals,
+                ..
+            } => match equals {
+                Some(equals) => write!(f, "http_header({url:?}, {header:?}, {equals:?})"),
+                None => write!(f, "http_header({url:?}, {header:?})"),
+            },
+            #[cfg(feature = "json")]
+            Wait::HttpJson {
+                url,
+                pointer,
+                equals,
+                ..
+            } => write!(f, "http_json({url:?}, {pointer:?}, {equals})"),
+            Wait::ModifiedSince {
+                path, reference, ..
+            } => match reference.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(since) => write!(f, "modified_since({path:?}, {})", since.as_secs()),
+                Err(e) => write!(f, "modified_since({path:?}, -{})", e.duration().as_secs()),
+            },
+            Wait::StdinLine { needle, .. } => write!(f, "stdin_line({needle:?})"),
+            #[cfg(all(unix, feature = "signal"))]
+            Wait::Signal { signum, .. } => write!(f, "signal({signum})"),
+            #[cfg(feature = "http")]
+            Wait::HttpLatency { url, max, .. } => {
+                write!(f, "http_latency({url:?}, {})", format_duration(*max))
+            }
+            #[cfg(feature = "http")]
+            Wait::HttpHead { url, status, .. } => write!(f, "http_head({url:?}, {status})"),
+            Wait::FileEmpty { path, .. } => write!(f, "file_empty({path:?})"),
+            #[cfg(unix)]
+            Wait::FileMode {
+                path, mask, expect, ..
+            } => write!(f, "file_mode({path:?}, {mask:#o}, {expect:#o})"),
+            #[cfg(feature = "http")]
+            Wait::HttpMulti {
+                urls, status, mode, ..
+            } => write!(f, "http_multi({mode}, {urls:?}, {status})"),
+            #[cfg(feature = "chrono")]
+            Wait::TimeOfDay { hour, minute, .. } => {
+                write!(f, "time_of_day({hour:02}:{minute:02})")
+            }
+            Wait::EnvVar { name, equals, .. } => match equals {
+                Some(value) => write!(f, "env({name:?}, {value:?})"),
+                None => write!(f, "env({name:?})"),
+            },
+            Wait::Command {
+                program,
+                args,
+                expected_code,
+                ..
+            } => write!(f, "command({program:?}, {args:?}, {expected_code})"),
+            Wait::Custom { .. } | Wait::CustomClosure { .. } | Wait::CustomStateful { .. } => {
+                f.write_str("custom()")
+            }
+            #[cfg(any(unix, windows))]
+            Wait::Pid { pid, .. } => write!(f, "pid_exited({pid})"),
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            Wait::ProcessNamed { name, .. } => write!(f, "process({name:?})"),
+            Wait::Throttled {
+                inner, interval, ..
+            } => write!(f, "throttled({inner}, {})", format_duration(*interval)),
+        }
+    }
+}
+
+/// Compares conditions structurally: two `Wait`s are equal if they're the same
+/// variant with the same configuration (including `not`).
+///
+/// Runtime state (eg, the last seen modified time of an `Update`, or the last
+/// result of a `Throttled`) is ignored, so a condition equals a fresh copy of
+/// itself however many times either has been checked. `Custom` conditions are
+/// equal if their function pointers are equal, `CustomClosure`s and
+/// `CustomStateful`s if they share the same closure (ie, one is a clone of the
+/// other), and `HttpBodyRegex`es if their patterns are written the same.
+impl PartialEq for Wait {
+    fn eq(&self, other: &Self) -> bool {
+        if std::mem::discriminant(self) != std::mem::discriminant(other) {
+            return false;
+        }
+
+        match (self, other) {
+            (
+                Wait::Elapsed { not, end_instant },
+                Wait::Elapsed {
+                    not: not2,
+                    end_instant: end_instant2,
+                },
+            ) => not == not2 && end_instant == end_instant2,
+            (
+                Wait::Until { not, when },
+                Wait::Until {
+                    not: not2,
+                    when: when2,
+                },
+            ) => not == not2 && when == when2,
+            #[cfg(feature = "cron")]
+            (
+                Wait::Schedule { not, cron, .. },
+                Wait::Schedule {
+                    not: not2,
+                    cron: cron2,
+                    ..
+                },
+            ) => not == not2 && cron == cron2,
+            (
+                Wait::Exists { not, path },
+                Wait::Exists {
+                    not: not2,
+                    path: path2,
+                },
+            )
+            | (
+                Wait::DirExists { not, path },
+                Wait::DirExists {
+                    not: not2,
+                    path: path2,
+                },
+            )
+            | (
+                Wait::DirNonEmpty { not, path },
+                Wait::DirNonEmpty {
+                    not: not2,
+                    path: path2,
+                },
+            ) => not == not2 && path == path2,
+            (
+                Wait::DirFileCount {
+                    not,
+                    path,
+                    min_count,
+                    extension,
+                    files_only,
+                },
+                Wait::DirFileCount {
+                    not: not2,
+                    path: path2,
+                    min_count: min_count2,
+                    extension: extension2,
+                    files_only: files_only2,
+                },
+            ) => {
+                not == not2
+                    && path == path2
+                    && min_count == min_count2
+                    && extension == extension2
+                    && files_only == files_only2
+            }
+            #[cfg(feature = "glob")]
+            (
+                Wait::GlobExists { not, pattern },
+                Wait::GlobExists {
+                    not: not2,
+                    pattern: pattern2,
+                },
+            ) => not == not2 && pattern == pattern2,
+            (
+                Wait::Update {
+                    not,
+                    path,
+                    require_exists,
+                    ..
+                },
+                Wait::Update {
+                    not: not2,
+                    path: path2,
+                    require_exists: require_exists2,
+                    ..
+                },
+            ) => not == not2 && path == path2 && require_exists == require_exists2,
+            (
+                Wait::UpdateSince {
+                    not,
+                    path,
+                    trigger_duration,
+                },
+                Wait::UpdateSince {
+                    not: not2,
+                    path: path2,
+                    trigger_duration: trigger_duration2,
+                },
+            ) => not == not2 && path == path2 && trigger_duration == trigger_duration2,
+            (
+                Wait::TcpHost { not, host, timeout },
+                Wait::TcpHost {
+                    not: not2,
+                    host: host2,
+                    timeout: timeout2,
+                },
+            ) => not == not2 && host == host2 && timeout == timeout2,
+            (
+                Wait::DnsResolves { not, host },
+                Wait::DnsResolves {
+                    not: not2,
+                    host: host2,
+                },
+            )
+            | (
+                Wait::PortFree { not, addr: host },
+                Wait::PortFree {
+                    not: not2,
+                    addr: host2,
+                },
+            ) => not == not2 && host == host2,
+            (
+                Wait::UdpHost {
+                    not,
+                    host,
+                    payload,
+                    expect_reply,
+                    timeout,
+                },
+                Wait::UdpHost {
+                    not: not2,
+                    host: host2,
+                    payload: payload2,
+                    expect_reply: expect_reply2,
+                    timeout: timeout2,
+                },
+            ) => {
+                not == not2
+                    && host == host2
+                    && payload == payload2
+                    && expect_reply == expect_reply2
+                    && timeout == timeout2
+            }
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpGet {
+                    not,
+                    url,
+                    status,
+                    options,
+                },
+                Wait::HttpGet {
+                    not: not2,
+                    url: url2,
+                    status: status2,
+                    options: options2,
+                },
+            ) => not == not2 && url == url2 && status == status2 && options == options2,
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpGetRange {
+                    not,
+                    url,
+                    statuses,
+                    options,
+                },
+                Wait::HttpGetRange {
+                    not: not2,
+                    url: url2,
+                    statuses: statuses2,
+                    options: options2,
+                },
+            ) => not == not2 && url == url2 && statuses == statuses2 && options == options2,
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpPost {
+                    not,
+                    url,
+                    body,
+                    content_type,
+                    status,
+                    options,
+                },
+                Wait::HttpPost {
+                    not: not2,
+                    url: url2,
+                    body: body2,
+                    content_type: content_type2,
+                    status: status2,
+                    options: options2,
+                },
+            ) => {
+                not == not2
+                    && url == url2
+                    && body == body2
+                    && content_type == content_type2
+                    && status == status2
+                    && options == options2
+            }
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpBody {
+                    not,
+                    url,
+                    contains,
+                    options,
+                },
+                Wait::HttpBody {
+                    not: not2,
+                    url: url2,
+                    contains: contains2,
+                    options: options2,
+                },
+            ) => not == not2 && url == url2 && contains == contains2 && options == options2,
+            #[cfg(all(feature = "http", feature = "regex"))]
+            (
+                Wait::HttpBodyRegex {
+                    not,
+                    url,
+                    pattern,
+                    options,
+                },
+                Wait::HttpBodyRegex {
+                    not: not2,
+                    url: url2,
+                    pattern: pattern2,
+                    options: options2,
+                },
+            ) => {
+                not == not2
+                    && url == url2
+                    && pattern.as_str() == pattern2.as_str()
+                    && options == options2
+            }
+            (
+                Wait::FileSize {
+                    not,
+                    path,
+                    change,
+                    require_exists,
+                    ..
+                },
+                Wait::FileSize {
+                    not: not2,
+                    path: path2,
+                    change: change2,
+                    require_exists: require_exists2,
+                    ..
+                },
+            ) => {
+                not == not2
+                    && path == path2
+                    && change == change2
+                    && require_exists == require_exists2
+            }
+            (
+                Wait::FileSizeAtLeast {
+                    not,
+                    path,
+                    min_bytes,
+                },
+                Wait::FileSizeAtLeast {
+                    not: not2,
+                    path: path2,
+                    min_bytes: min_bytes2,
+                },
+            ) => not == not2 && path == path2 && min_bytes == min_bytes2,
+            (
+                Wait::FileContains {
+                    not, path, needle, ..
+                },
+                Wait::FileContains {
+                    not: not2,
+                    path: path2,
+                    needle: needle2,
+                    ..
+                },
+            ) => not == not2 && path == path2 && needle == needle2,
+            (
+                Wait::FileHashStable { not, path, .. },
+                Wait::FileHashStable {
+                    not: not2,
+                    path: path2,
+                    ..
+                },
+            ) => not == not2 && path == path2,
+            (
+                Wait::FileUnlocked { not, path },
+                Wait::FileUnlocked {
+                    not: not2,
+                    path: path2,
+                },
+            ) => not == not2 && path == path2,
+            #[cfg(any(unix, windows))]
+            (
+                Wait::DiskFree {
+                    not,
+                    path,
+                    min_bytes,
+                },
+                Wait::DiskFree {
+                    not: not2,
+                    path: path2,
+                    min_bytes: min_bytes2,
+                },
+            ) => not == not2 && path == path2 && min_bytes == min_bytes2,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            (
+                Wait::MemoryFree { not, min_bytes },
+                Wait::MemoryFree {
+                    not: not2,
+                    min_bytes: min_bytes2,
+                },
+            ) => not == not2 && min_bytes == min_bytes2,
+            #[cfg(unix)]
+            (
+                Wait::LoadBelow {
+                    not,
+                    threshold,
+                    window,
+                },
+                Wait::LoadBelow {
+                    not: not2,
+                    threshold: threshold2,
+                    window: window2,
+                },
+            ) => not == not2 && threshold == threshold2 && window == window2,
+            (
+                Wait::TcpBanner {
+                    not,
+                    host,
+                    expect,
+                    read_timeout,
+                },
+                Wait::TcpBanner {
+                    not: not2,
+                    host: host2,
+                    expect: expect2,
+                    read_timeout: read_timeout2,
+                },
+            ) => not == not2 && host == host2 && expect == expect2 && read_timeout == read_timeout2,
+            #[cfg(unix)]
+            (
+                Wait::UnixSocket { not, path },
+                Wait::UnixSocket {
+                    not: not2,
+                    path: path2,
+                },
+            ) => not == not2 && path == path2,
+            #[cfg(unix)]
+            (
+                Wait::FifoReady { not, path },
+                Wait::FifoReady {
+                    not: not2,
+                    path: path2,
+                },
+            ) => not == not2 && path == path2,
+            (Wait::Always { not }, Wait::Always { not: not2 }) => not == not2,
+            (Wait::Never { not }, Wait::Never { not: not2 }) => not == not2,
+            (Wait::CustomStateful { f, not }, Wait::CustomStateful { f: f2, not: not2 }) => {
+                not == not2 && Arc::ptr_eq(&f.0, &f2.0)
+            }
+            #[cfg(feature = "tls")]
+            (
+                Wait::TlsCertValid {
+                    not,
+                    host,
+                    min_remaining,
+                    timeout,
+                },
+                Wait::TlsCertValid {
+                    not: not2,
+                    host: host2,
+                    min_remaining: min_remaining2,
+                    timeout: timeout2,
+                },
+            ) => {
+                not == not2
+                    && host == host2
+                    && min_remaining == min_remaining2
+                    && timeout == timeout2
+            }
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpHeader {
+                    not,
+                    url,
+                    header,
+                    equals,
+                    options,
+                },
+                Wait::HttpHeader {
+                    not: not2,
+                    url: url2,
+                    header: header2,
+                    equals: equals2,
+                    options: options2,
+                },
+            ) => {
+                not == not2
+                    && url == url2
+                    && header.eq_ignore_ascii_case(header2)
+                    && equals == equals2
+                    && options == options2
+            }
+            #[cfg(feature = "json")]
+            (
+                Wait::HttpJson {
+                    not,
+                    url,
+                    pointer,
+                    equals,
+                    options,
+                },
+                Wait::HttpJson {
+                    not: not2,
+                    url: url2,
+                    pointer: pointer2,
+                    equals: equals2,
+                    options: options2,
+                },
+            ) => {
+                not == not2
+                    && url == url2
+                    && pointer == pointer2
+                    && equals == equals2
+                    && options == options2
+            }
+            (
+                Wait::ModifiedSince {
+                    not,
+                    path,
+                    reference,
+                },
+                Wait::ModifiedSince {
+                    not: not2,
+                    path: path2,
+                    reference: reference2,
+                },
+            ) => not == not2 && path == path2 && reference == reference2,
+            (
+                Wait::StdinLine { not, needle, .. },
+                Wait::StdinLine {
+                    not: not2,
+                    needle: needle2,
+                    ..
+                },
+            ) => not == not2 && needle == needle2,
+            #[cfg(all(unix, feature = "signal"))]
+            (
+                Wait::Signal { not, signum, .. },
+                Wait::Signal {
+                    not: not2,
+                    signum: signum2,
+                    ..
+                },
+            ) => not == not2 && signum == signum2,
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpLatency {
+                    not,
+                    url,
+                    max,
+                    options,
+                },
+                Wait::HttpLatency {
+                    not: not2,
+                    url: url2,
+                    max: max2,
+                    options: options2,
+                },
+            ) => not == not2 && url == url2 && max == max2 && options == options2,
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpHead {
+                    not,
+                    url,
+                    status,
+                    options,
+                },
+                Wait::HttpHead {
+                    not: not2,
+                    url: url2,
+                    status: status2,
+                    options: options2,
+                },
+            ) => not == not2 && url == url2 && status == status2 && options == options2,
+            (
+                Wait::FileEmpty { not, path },
+                Wait::FileEmpty {
+                    not: not2,
+                    path: path2,
+                },
+            ) => not == not2 && path == path2,
+            #[cfg(unix)]
+            (
+                Wait::FileMode {
+                    not,
+                    path,
+                    mask,
+                    expect,
+                },
+                Wait::FileMode {
+                    not: not2,
+                    path: path2,
+                    mask: mask2,
+                    expect: expect2,
+                },
+            ) => not == not2 && path == path2 && mask == mask2 && expect == expect2,
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpMulti {
+                    not,
+                    urls,
+                    status,
+                    mode,
+                    options,
+                },
+                Wait::HttpMulti {
+                    not: not2,
+                    urls: urls2,
+                    status: status2,
+                    mode: mode2,
+                    options: options2,
+                },
+            ) => {
+                not == not2
+                    && urls == urls2
+                    && status == status2
+                    && mode == mode2
+                    && options == options2
+            }
+            #[cfg(feature = "chrono")]
+            (
+                Wait::TimeOfDay {
+                    not, hour, minute, ..
+                },
+                Wait::TimeOfDay {
+                    not: not2,
+                    hour: hour2,
+                    minute: minute2,
+                    ..
+                },
+            ) => not == not2 && hour == hour2 && minute == minute2,
+            (
+                Wait::EnvVar { not, name, equals },
+                Wait::EnvVar {
+                    not: not2,
+                    name: name2,
+                    equals: equals2,
+                },
+            ) => not == not2 && name == name2 && equals == equals2,
+            (
+                Wait::Command {
+                    not,
+                    program,
+                    args,
+                    expected_code,
+                },
+                Wait::Command {
+                    not: not2,
+                    program: program2,
+                    args: args2,
+                    expected_code: expected_code2,
+                },
+            ) => {
+                not == not2
+                    && program == program2
+                    && args == args2
+                    && expected_code == expected_code2
+            }
+            (Wait::Custom { f, not }, Wait::Custom { f: f2, not: not2 }) => {
+                not == not2 && std::ptr::fn_addr_eq(*f, *f2)
+            }
+            (Wait::CustomClosure { f, not }, Wait::CustomClosure { f: f2, not: not2 }) => {
+                not == not2 && Arc::ptr_eq(&f.0, &f2.0)
+            }
+            #[cfg(any(unix, windows))]
+            (
+                Wait::Pid { not, pid },
+                Wait::Pid {
+                    not: not2,
+                    pid: pid2,
+                },
+            ) => not == not2 && pid == pid2,
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            (
+                Wait::ProcessNamed { not, name },
+                Wait::ProcessNamed {
+                    not: not2,
+                    name: name2,
+                },
+            ) => not == not2 && name == name2,
+            (
+                Wait::Throttled {
+                    not,
+                    inner,
+                    interval,
+                    ..
+                },
+                Wait::Throttled {
+                    not: not2,
+                    inner: inner2,
+                    interval: interval2,
+                    ..
+                },
+            ) => not == not2 && inner == inner2 && interval == interval2,
+            // The variants are the same, so each pairs with itself above. Every one
+            // is listed, so that a new one can't be left out
+            (
+                Wait::Elapsed { .. }
+                | Wait::Until { .. }
+                | Wait::Exists { .. }
+                | Wait::DirExists { .. }
+                | Wait::DirNonEmpty { .. }
+                | Wait::DirFileCount { .. }
+                | Wait::Update { .. }
+                | Wait::UpdateSince { .. }
+                | Wait::TcpHost { .. }
+                | Wait::DnsResolves { .. }
+                | Wait::PortFree { .. }
+                | Wait::UdpHost { .. }
+                | Wait::FileSize { .. }
+                | Wait::FileSizeAtLeast { .. }
+                | Wait::FileContains { .. }
+                | Wait::FileHashStable { .. }
+                | Wait::FileUnlocked { .. }
+                | Wait::TcpBanner { .. }
+                | Wait::Always { .. }
+                | Wait::Never { .. }
+                | Wait::ModifiedSince { .. }
+                | Wait::StdinLine { .. }
+                | Wait::FileEmpty { .. }
+                | Wait::EnvVar { .. }
+                | Wait::Command { .. }
+                | Wait::Custom { .. }
+                | Wait::CustomClosure { .. }
+                | Wait::CustomStateful { .. }
+                | Wait::Throttled { .. },
+                _,
+            ) => unreachable!(),
+            #[cfg(feature = "cron")]
+            (Wait::Schedule { .. }, _) => unreachable!(),
+            #[cfg(feature = "glob")]
+            (Wait::GlobExists { .. }, _) => unreachable!(),
+            #[cfg(feature = "http")]
+            (
+                Wait::HttpGet { .. }
+                | Wait::HttpGetRange { .. }
+                | Wait::HttpPost { .. }
+                | Wait::HttpBody { .. }
+                | Wait::HttpHeader { .. }
+                | Wait::HttpLatency { .. }
+                | Wait::HttpHead { .. }
+                | Wait::HttpMulti { .. },
+                _,
+            ) => unreachable!(),
+            #[cfg(all(feature = "http", feature = "regex"))]
+            (Wait::HttpBodyRegex { .. }, _) => unreachable!(),
+            #[cfg(feature = "json")]
+            (Wait::HttpJson { .. }, _) => unreachable!(),
+            #[cfg(feature = "tls")]
+            (Wait::TlsCertValid { .. }, _) => unreachable!(),
+            #[cfg(feature = "chrono")]
+            (Wait::TimeOfDay { .. }, _) => unreachable!(),
+            #[cfg(all(unix, feature = "signal"))]
+            (Wait::Signal { .. }, _) => unreachable!(),
+            #[cfg(unix)]
+            (
+                Wait::LoadBelow { .. }
+                | Wait::UnixSocket { .. }
+                | Wait::FifoReady { .. }
+                | Wait::FileMode { .. },
+                _,
+            ) => unreachable!(),
+            #[cfg(any(unix, windows))]
+            (Wait::DiskFree { .. } | Wait::Pid { .. }, _) => unreachable!(),
+            #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+            (Wait::MemoryFree { .. } | Wait::ProcessNamed { .. }, _) => unreachable!(),
+        }
+    }
+}
+
+/// Roughly how costly a condition is to check, from cheapest to costliest. See
+/// [Wait::estimated_cost].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Cost {
+    /// Only inspects local state, eg, the clock or file metadata.
+    Local,
+    /// Runs a subprocess.
+    Subprocess,
+    /// Goes over the network, possibly waiting for a timeout.
+    Network,
+}
+
+/// The direction of change that a [Wait::FileSize] waits for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SizeChange {
+    /// The file grew or shrank.
+    #[default]
+    Any,
+    /// The file grew.
+    Grow,
+    /// The file shrank, eg, because it was truncated.
+    Shrink,
+}
+
+impl SizeChange {
+    /// Whether a change in size from `prev` to `curr` is in this direction.
+    fn matches(self, prev: u64, curr: u64) -> bool {
+        match self {
+            SizeChange::Any => curr != prev,
+            SizeChange::Grow => curr > prev,
+            SizeChange::Shrink => curr < prev,
+        }
+    }
+}
+
+/// Which load average a [Wait::LoadBelow] compares.
+#[cfg(unix)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoadWindow {
+    /// The average over the last minute.
+    OneMinute,
+    /// The average over the last five minutes.
+    FiveMinutes,
+    /// The average over the last fifteen minutes.
+    FifteenMinutes,
+}
+
+#[cfg(unix)]
+impl std::fmt::Display for LoadWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadWindow::OneMinute => f.write_str("1m"),
+            LoadWindow::FiveMinutes => f.write_str("5m"),
+            LoadWindow::FifteenMinutes => f.write_str("15m"),
+        }
+    }
+}
+
+/// A closure used by [Wait::CustomClosure]. Clones share the same closure.
+#[derive(Clone)]
+pub struct CustomFn(Arc<dyn Fn() -> bool + Send + Sync>);
+
+impl CustomFn {
+    /// Calls the underlying closure.
+    pub fn call(&self) -> bool {
+        (self.0)()
+    }
+}
+
+impl std::fmt::Debug for CustomFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CustomFn(..)")
+    }
+}
+
+/// A closure used by [Wait::CustomStateful]. Clones share the same closure, and
+/// so its state.
+#[derive(Clone)]
+pub struct CustomFnMut(Arc<Mutex<dyn FnMut() -> bool + Send>>);
+
+impl CustomFnMut {
+    /// Calls the underlying closure, holding its lock until it returns.
+    pub fn call(&self) -> bool {
+        let mut f = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        f()
+    }
+}
+
+impl std::fmt::Debug for CustomFnMut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CustomFnMut(..)")
+    }
+}
+
+/// The runtime state of conditions like [Wait::FileSize]: a [std::cell::Cell]
+/// that can be shared between threads, so that `Wait` is [Sync]. Clones start
+/// with the same value but are independent afterwards.
+#[derive(Default)]
+pub struct SyncCell<T>(Mutex<T>);
+
+impl<T: Copy> SyncCell<T> {
+    /// Creates a cell holding `value`.
+    pub fn new(value: T) -> Self {
+        Self(Mutex::new(value))
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> T {
+        *self.lock()
+    }
+
+    /// Sets the current value.
+    pub fn set(&self, value: T) {
+        *self.lock() = value;
+    }
+
+    /// Sets the current value, returning the previous one.
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.lock(), value)
+    }
+
+    /// Reads and modifies the current value with `f`, returning what it returns.
+    /// The value is locked throughout, so concurrent checks can't interleave
+    /// their updates. `f` mustn't use this cell itself, which would deadlock.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, T> {
+        // The value is always valid, even if a thread panicked while holding the lock
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Copy> Clone for SyncCell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+impl<T: Copy + std::fmt::Debug> std::fmt::Debug for SyncCell<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SyncCell").field(&self.get()).finish()
+    }
+}
+
+/// Whether a signal has been delivered, as set by the handler registered for a
+/// [Wait::Signal]. Clones share the same flag, and the handler is unregistered
+/// once the last of them is dropped.
+#[cfg(all(unix, feature = "signal"))]
+#[derive(Clone, Debug)]
+pub struct SignalFlag(Arc<SignalRegistration>);
+
+#[cfg(all(unix, feature = "signal"))]
+#[derive(Debug)]
+struct SignalRegistration {
+    received: Arc<std::sync::atomic::AtomicBool>,
+    id: signal_hook::SigId,
+}
+
+#[cfg(all(unix, feature = "signal"))]
+impl SignalFlag {
+    /// Registers a handler for `signum` that sets this flag.
+    fn register(signum: i32) -> std::io::Result<Self> {
+        // signal-hook panics rather than failing for these
+        if signal_hook::consts::FORBIDDEN.contains(&signum) {
+            let message = format!("signal {signum} can't be handled");
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                message,
+            ));
+        }
+
+        let received = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let id = signal_hook::flag::register(signum, received.clone())?;
+        Ok(Self(Arc::new(SignalRegistration { received, id })))
+    }
+
+    /// Whether the signal has been delivered since this was registered (or last
+    /// reset).
+    pub fn get(&self) -> bool {
+        self.0.received.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn reset(&self) {
+        self.0
+            .received
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(all(unix, feature = "signal"))]
+impl Drop for SignalRegistration {
+    fn drop(&mut self) {
+        signal_hook::low_level::unregister(self.id);
+    }
+}
+
+/// (De)serializes an [Instant] as the [Duration] remaining until it.
+#[cfg(feature = "serde")]
+mod serde_instant {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::{Duration, Instant};
+
+    pub fn serialize<S: Serializer>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error> {
+        instant
+            .saturating_duration_since(Instant::now())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Instant, D::Error> {
+        let remaining = Duration::deserialize(deserializer)?;
+        Ok(Instant::now() + remaining)
+    }
+}
+
+/// The default timeout for each TCP connection attempt.
+pub const DEFAULT_TCP_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[cfg(feature = "serde")]
+fn default_tcp_timeout() -> Duration {
+    DEFAULT_TCP_TIMEOUT
+}
+
+/// Checks whether a TCP connection can be established to `host`, trying each
+/// address it resolves to (in the order they're resolved) until one succeeds.
+/// Each attempt gives up after `timeout`.
+fn tcp_connects(host: &str, timeout: Duration) -> bool {
+    match host.to_socket_addrs() {
+        Ok(addrs) => connects_any(addrs, timeout),
+        Err(_) => false,
+    }
+}
+
+/// Whether a TCP connection can be established to any of `addrs`, trying each in
+/// order and stopping at the first that connects.
+fn connects_any<I>(addrs: I, timeout: Duration) -> bool
+where
+    I: IntoIterator<Item = std::net::SocketAddr>,
+{
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, timeout).is_ok())
+}
+
+/// The most that [Wait::TcpBanner] reads of a server's banner.
+pub const MAX_BANNER_BYTES: usize = 512;
+
+/// Connects to `host` and reads its banner: up to [MAX_BANNER_BYTES], stopping
+/// after the first newline. Each address that `host` resolves to is tried until
+/// one connects. Connecting and reading each give up after `timeout`, in which
+/// case whatever was read is returned.
+fn tcp_banner(host: &str, timeout: Duration) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let mut stream = host
+        .to_socket_addrs()
+        .ok()?
+        .find_map(|addr| TcpStream::connect_timeout(&addr, timeout).ok())?;
+
+    // Read timeouts apply to each read, so a server trickling bytes out is held
+    // to an overall deadline for the whole banner
+    let deadline = Instant::now() + timeout;
+    let mut banner = Vec::new();
+    let mut buf = [0; MAX_BANNER_BYTES];
+    while banner.len() < MAX_BANNER_BYTES && !banner.contains(&b'\n') {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || stream.set_read_timeout(Some(remaining)).is_err() {
+            break;
+        }
+        match stream.read(&mut buf[..MAX_BANNER_BYTES - banner.len()]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => banner.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    Some(banner)
+}
+
+/// Looks up `host`, returning whether any addresses were found. An `Err` means the
+/// lookup itself failed.
+fn dns_resolves(host: &str) -> std::io::Result<bool> {
+    let mut addrs = if validate_host_port(host) {
+        host.to_socket_addrs()?
+    } else {
+        (host, 0).to_socket_addrs()?
+    };
+
+    Ok(addrs.next().is_some())
+}
+
+/// The default timeout for receiving a UDP reply.
+pub const DEFAULT_UDP_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[cfg(feature = "serde")]
+fn default_udp_timeout() -> Duration {
+    DEFAULT_UDP_TIMEOUT
+}
+
+/// Sends `payload` to `host` over UDP and, if `expect_reply`, waits up to
+/// `timeout` for a reply.
+fn udp_reachable(
+    host: &str,
+    payload: &[u8],
+    expect_reply: bool,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    udp_reachable_any(host.to_socket_addrs()?, payload, expect_reply, timeout)
+}
+
+/// Tries [udp_reachable_at] with each of `addrs` in turn, until one succeeds.
+/// Fails with the last address's error, or `NotFound` if there are none.
+fn udp_reachable_any<I>(
+    addrs: I,
+    payload: &[u8],
+    expect_reply: bool,
+    timeout: Duration,
+) -> std::io::Result<()>
+where
+    I: IntoIterator<Item = SocketAddr>,
+{
+    let mut result = Err(std::io::ErrorKind::NotFound.into());
+    for addr in addrs {
+        result = udp_reachable_at(addr, payload, expect_reply, timeout);
+        if result.is_ok() {
+            break;
+        }
+    }
+    result
+}
+
+/// Sends `payload` to `addr` and, if `expect_reply` is set, receives a reply
+/// within `timeout`.
+fn udp_reachable_at(
+    addr: SocketAddr,
+    payload: &[u8],
+    expect_reply: bool,
+    timeout: Duration,
+) -> std::io::Result<()> {
+    let local = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+
+    let socket = UdpSocket::bind(local)?;
+    socket.connect(addr)?;
+    socket.send(payload)?;
+
+    if expect_reply {
+        // A zero timeout is an error, so wait at least a little
+        socket.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        socket.recv(&mut [0; 65536])?;
+    }
+
+    Ok(())
+}
+
+/// Counts the entries of the directory `path` that aren't hidden and match
+/// `extension` and `files_only`. A directory that can't be read has none.
+fn dir_file_count(path: &Path, extension: Option<&str>, files_only: bool) -> usize {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter(|entry| match extension {
+            Some(ext) => entry.path().extension() == Some(std::ffi::OsStr::new(ext)),
+            None => true,
+        })
+        // `DirEntry::file_type` doesn't follow symlinks, but `Path::is_file` does
+        .filter(|entry| !files_only || entry.path().is_file())
+        .count()
+}
+
+/// Reads `path` from `offset`, returning whether any line contains `needle`.
+/// `offset` is advanced past every complete line that doesn't, and reset if the
+/// file has shrunk below it.
+fn file_contains(path: &Path, needle: &str, offset: &mut u64) -> std::io::Result<bool> {
+    use std::io::{BufRead, BufReader, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    if file.metadata()?.len() < *offset {
+        *offset = 0;
+    }
+    file.seek(SeekFrom::Start(*offset))?;
+
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            return Ok(false);
+        }
+        if String::from_utf8_lossy(&line).contains(needle) {
+            return Ok(true);
+        }
+        if line.last() != Some(&b'\n') {
+            // A partial line may be completed (and match) later, so read it again
+            return Ok(false);
+        }
+        *offset += read as u64;
+    }
+}
+
+/// The next local time after now that matches `cron`.
+#[cfg(feature = "cron")]
+fn next_scheduled(cron: &str) -> Option<SystemTime> {
+    let schedule = cron.parse::<cron::Schedule>().ok()?;
+    let next = schedule.upcoming(chrono::Local).next()?;
+    Some(next.into())
+}
+
+/// Whether a [Wait::TimeOfDay] completes at the local time `now`: it's at or
+/// after `hour:minute`, and it hasn't already completed today according to
+/// `last_fired_day`, which is updated if it does.
+#[cfg(feature = "chrono")]
+fn time_of_day_reached(
+    now: chrono::NaiveDateTime,
+    hour: u8,
+    minute: u8,
+    last_fired_day: &SyncCell<Option<i32>>,
+) -> bool {
+    use chrono::{Datelike, Timelike};
+
+    let today = now.date().num_days_from_ce();
+    let reached = (now.hour(), now.minute()) >= (u32::from(hour), u32::from(minute));
+    if !reached || last_fired_day.get() == Some(today) {
+        return false;
+    }
+
+    last_fired_day.set(Some(today));
+    true
+}
+
+/// Whether `end_instant` has elapsed as of `now`, which it has at that very instant.
+fn elapsed(end_instant: Instant, now: Instant) -> bool {
+    now >= end_instant
+}
+
+fn get_modified_time(path: &Path) -> Option<SystemTime> {
+    let meta = path.metadata().ok()?;
+    meta.modified().ok()
+}
+
+fn get_file_size(path: &Path) -> Option<u64> {
+    let meta = path.metadata().ok()?;
+    Some(meta.len())
+}
+
+/// Hashes the contents of `path`, or `None` if it can't be read.
+fn get_file_hash(path: &Path) -> Option<u64> {
+    use std::{
+        hash::{DefaultHasher, Hasher},
+        io::Read,
+    };
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = [0; 8192];
+    loop {
+        match file.read(&mut buf).ok()? {
+            0 => return Some(hasher.finish()),
+            n => hasher.write(&buf[..n]),
+        }
+    }
+}
+
+/// Whether the file at `path` can be locked exclusively. The lock is released as
+/// soon as it's taken, when the file is closed.
+fn file_unlocked(path: &Path) -> bool {
+    match std::fs::File::open(path) {
+        Ok(file) => file.try_lock().is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Whether the FIFO at `path` has data to read. It's opened without blocking, so a
+/// FIFO without a writer can be checked, and closed again without reading.
+#[cfg(unix)]
+fn fifo_ready(path: &Path) -> bool {
+    use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+    use std::os::unix::io::AsRawFd;
+
+    // A regular file would always poll as readable
+    if !std::fs::metadata(path).is_ok_and(|m| m.file_type().is_fifo()) {
+        return false;
+    }
+
+    let fifo = match std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+    {
+        Ok(fifo) => fifo,
+        Err(_) => return false,
+    };
+
+    let mut pollfd = libc::pollfd {
+        fd: fifo.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // Without a writer, some platforms report only POLLHUP, which isn't data
+    let polled = unsafe { libc::poll(&mut pollfd, 1, 0) };
+    polled > 0 && pollfd.revents & libc::POLLIN != 0
+}
+
+/// An error from [parse_duration].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseDurationError {
+    /// The duration was an empty string.
+    Empty,
+    /// A character other than a digit or a unit (`w`, `d`, `h`, `m`, `s`, or
+    /// `ms`) was found.
+    UnexpectedChar(char),
+    /// A unit wasn't preceded by a number, as in `"h"` or `"1hm"`.
+    MissingNumber(char),
+    /// The duration is too long to represent.
+    Overflow,
+}
+
+impl std::fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseDurationError::Empty => write!(f, "empty duration"),
+            ParseDurationError::UnexpectedChar(c) => {
+                write!(f, "unexpected character '{c}' in duration")
+            }
+            ParseDurationError::MissingNumber(c) => {
+                write!(f, "unit '{c}' in duration has no number before it")
+            }
+            ParseDurationError::Overflow => write!(f, "duration is too long"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+/// Parses a simple human-readable duration, returning a `Duration`
+///
+/// "3h10m" -> 11400 seconds
+///
+/// The units are `w` (weeks), `d` (days), `h` (hours), `m` (minutes), `s`
+/// (seconds), and `ms` (milliseconds), so "1m500ms" is 60.5 seconds. The rules
+/// are:
+///
+/// * An empty string is an error ([ParseDurationError::Empty]), rather than
+///   zero. Write `"0s"` (or `"0"`) for that.
+/// * Every unit must follow a number, so `"h"` and `"1hm"` are errors
+///   ([ParseDurationError::MissingNumber]).
+/// * A number at the end without a unit is in seconds, so `"90"` and `"1m30"`
+///   are both 90 seconds.
+/// * Nothing else is allowed, including whitespace, signs, and fractions.
+pub fn parse_duration(duration: &str) -> Result<Duration, ParseDurationError> {
+    if duration.is_empty() {
+        return Err(ParseDurationError::Empty);
+    }
+
+    let mut total_delay = Duration::ZERO;
+
+    // Every step is checked, so that a huge duration is an error rather than
+    // silently wrapping around to a small one
+    let mut acc: u64 = 0;
+    let mut has_number = false;
+    let mut chars = duration.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            acc = acc
+                .checked_mul(10)
+                .and_then(|acc| acc.checked_add(c.to_digit(10).unwrap() as u64))
+                .ok_or(ParseDurationError::Overflow)?;
+            has_number = true;
+            continue;
+        }
+        if !has_number && matches!(c, 'w' | 'd' | 'h' | 'm' | 's') {
+            return Err(ParseDurationError::MissingNumber(c));
+        }
+
+        let delay = match c {
+            'w' => secs(acc, 604800)?, // weeks
+            'd' => secs(acc, 86400)?,  // days
+            'h' => secs(acc, 3600)?,   // hours
+            'm' if chars.next_if_eq(&'s').is_some() => Duration::from_millis(acc),
+            'm' => secs(acc, 60)?, // minutes
+            's' => secs(acc, 1)?,  // seconds
+            _ => return Err(ParseDurationError::UnexpectedChar(c)),
+        };
+
+        total_delay = total_delay
+            .checked_add(delay)
+            .ok_or(ParseDurationError::Overflow)?;
+        acc = 0;
+        has_number = false;
+    }
+
+    // A trailing number without a unit is in seconds (and is zero if there's
+    // none, since the string ended with a unit)
+    total_delay
+        .checked_add(Duration::from_secs(acc))
+        .ok_or(ParseDurationError::Overflow)
+}
+
+/// Formats `duration` in the units that [parse_duration] accepts, eg, `1h30m`.
+/// Durations of a second or more are rounded to the nearest second; shorter ones
+/// are shown in milliseconds.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        return format!("{}ms", duration.as_millis());
+    }
+
+    let mut secs = (duration + Duration::from_millis(500)).as_secs();
+    let mut formatted = String::new();
+    for (unit, unit_secs) in [('d', 86400), ('h', 3600), ('m', 60)] {
+        if secs >= unit_secs {
+            formatted += &format!("{}{unit}", secs / unit_secs);
+            secs %= unit_secs;
+        }
+    }
+    if secs > 0 || formatted.is_empty() {
+        formatted += &format!("{secs}s");
+    }
+    formatted
+}
+
+/// `count` units of `unit_secs` seconds each.
+fn secs(count: u64, unit_secs: u64) -> Result<Duration, ParseDurationError> {
+    count
+        .checked_mul(unit_secs)
+        .map(Duration::from_secs)
+        .ok_or(ParseDurationError::Overflow)
+}
+
+/// An error from [parse_http_get].
+#[cfg(feature = "http")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpArgError {
+    /// The status before the `,` isn't a number.
+    InvalidStatus(String),
+    /// The status is a number, but not a valid HTTP status (100 to 599).
+    StatusOutOfRange(String),
+}
+
+#[cfg(feature = "http")]
+impl std::fmt::Display for HttpArgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpArgError::InvalidStatus(s) => write!(f, "invalid HTTP status '{s}'"),
+            HttpArgError::StatusOutOfRange(s) => {
+                write!(f, "HTTP status '{s}' isn't between 100 and 599")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl std::error::Error for HttpArgError {}
+
+/// Parses an input argument for an HTTP GET into the expected status code and URL to hit.
+///
+/// The argument is either `STATUS,URL`, such as `418,https://example.com`, or
+/// just a URL, which is expected to return 200. Anything before the first `,`
+/// that is only letters and digits is taken to be a status, so it must be a
+/// number from 100 to 599.
+///
+/// The URL is validated with the `url` crate, if possible, cleaning potential errors.
+/// If that fails, the URL is used as-is.
+#[cfg(feature = "http")]
+pub fn parse_http_get(urlarg: &str) -> Result<(u16, String), HttpArgError> {
+    let (status_code, urlarg) = match urlarg.split_once(',') {
+        Some((status, url))
+            if !status.is_empty() && status.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            if !status.chars().all(|c| c.is_ascii_digit()) {
+                return Err(HttpArgError::InvalidStatus(status.to_string()));
+            }
+            match status.parse::<u16>() {
+                Ok(code @ 100..=599) => (code, url),
+                _ => return Err(HttpArgError::StatusOutOfRange(status.to_string())),
+            }
+        }
+        _ => (200, urlarg),
+    };
+
+    if let Some(url) = parse_url(urlarg) {
+        Ok((status_code, url.to_string()))
+    } else {
+        Ok((status_code, urlarg.to_string()))
+    }
+}
+
+/// Tries to parse a URL using the `url` crate.
+#[cfg(feature = "http")]
+fn parse_url(urlarg: &str) -> Option<Url> {
+    let violations = std::cell::RefCell::new(Vec::new());
+    let url = Url::options()
+        .syntax_violation_callback(Some(&|v| {
+            violations.borrow_mut().push(v);
+        }))
+        .parse(urlarg)
+        .ok()?;
+
+    Some(url)
+}
+
+/// An error for a host that isn't a valid `hostname:port`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidHost(pub String);
+
+impl std::fmt::Display for InvalidHost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid host:port '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidHost {}
+
+/// Checks that the input appears to be a valid `hostname:port` input, where `port`
+/// is a u16.
+#[deprecated(note = "renamed to `validate_host_port`")]
+pub fn validate_tcp(hostarg: &str) -> bool {
+    validate_host_port(hostarg)
+}
+
+/// Checks that the input appears to be a valid `hostname:port` input, where `port`
+/// is a nonzero u16. This is suitable for both TCP and UDP hosts.
+///
+/// Port 0 is rejected: binding to it picks an arbitrary port, but nothing can be
+/// reached at it.
+///
+/// IPv6 addresses must be bracketed, as in `[::1]:8080`; without brackets, an
+/// input like `::1:80` is ambiguous about where the address ends.
+pub fn validate_host_port(hostarg: &str) -> bool {
+    if let Some(rest) = hostarg.strip_prefix('[') {
+        // A bracketed IPv6 address, which must be followed by the port
+        return match rest.split_once("]:") {
+            Some((addr, port)) => addr.parse::<Ipv6Addr>().is_ok() && valid_port(port),
+            None => false,
+        };
+    }
+
+    // Assume that the last location of ':' is the delimiter for the port
+    match hostarg.rsplit_once(':') {
+        // Everything after the colon should be a u16 port number. Any other colon
+        // means an unbracketed IPv6 address.
+        Some((host, port)) => !host.contains(':') && valid_port(port),
+        // There's no ':' in the input, so assume this isn't a host to which we can connect
+        None => false,
+    }
+}
+
+fn valid_port(port: &str) -> bool {
+    port.parse::<u16>().is_ok_and(|port| port != 0)
+}
+
+/// Validates `hostarg` as with [validate_host_port], then resolves it, returning
+/// the first address found. `None` means that the input is invalid or that it
+/// doesn't (yet) resolve.
+///
+/// Conditions such as [Wait::TcpHost] keep the unresolved `hostname:port` and
+/// resolve it on every check, so that they can wait for a host that's still
+/// coming up or whose address changes.
+pub fn resolve_host_port(hostarg: &str) -> Option<SocketAddr> {
+    if !validate_host_port(hostarg) {
+        return None;
+    }
+    hostarg.to_socket_addrs().ok()?.next()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use super::Wait;
+        use std::time::Duration;
+
+        let w = !Wait::new_file_exists("foo.txt");
+        let json = serde_json::to_string(&w).unwrap();
+        assert_eq!(json, r#"{"Exists":{"not":true,"path":"foo.txt"}}"#);
+        let w: Wait = serde_json::from_str(&json).unwrap();
+        assert!(matches!(w, Wait::Exists { not: true, .. }));
+
+        let w = Wait::new_elapsed_from_duration(Duration::from_secs(3600));
+        let w: Wait = serde_json::from_str(&serde_json::to_string(&w).unwrap()).unwrap();
+        assert!(!w.condition_met());
+
+        assert!(serde_json::to_string(&Wait::new_custom(|| true)).is_err());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_get_status() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        let not_found = serve_http(http_response("404 Not Found", &[], ""));
+        assert!(Wait::new_http_get(&not_found, 404).condition_met());
+        assert!(!Wait::new_http_get(&not_found, 200).condition_met());
+        assert!(!(!Wait::new_http_get(&not_found, 404)).condition_met());
+        assert!((!Wait::new_http_get(&not_found, 200)).condition_met());
+
+        let error = serve_http(http_response("500 Internal Server Error", &[], ""));
+        assert!(Wait::new_http_get(&error, 500).condition_met());
+        assert!((!Wait::new_http_get(&error, 200)).condition_met());
+
+        let ok = serve_http(http_response("200 OK", &[], "hello"));
+        assert!(Wait::new_http_get(&ok, 200).condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_get_class() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+        use crate::StatusClass;
+
+        let url = serve_http(http_response("204 No Content", &[], ""));
+        assert!(Wait::new_http_get_class(&url, StatusClass::Success).condition_met());
+        assert!(Wait::new_http_get_range(&url, 200..=204).condition_met());
+        assert!(!Wait::new_http_get_range(&url, 200..=203).condition_met());
+
+        let url = serve_http(http_response("503 Service Unavailable", &[], ""));
+        assert!((!Wait::new_http_get_class(&url, StatusClass::Success)).condition_met());
+        assert!(Wait::new_http_get_class(&url, StatusClass::ServerError).condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_post_status() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        let created = serve_http(http_response("201 Created", &[], ""));
+        let w = Wait::new_http_post(&created, r#"{"probe":true}"#, "application/json", 201);
+        assert!(w.condition_met());
+        assert!(w.condition_met());
+        assert!(!(!w).condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_body_contains() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        let url = serve_http(http_response("200 OK", &[], r#"{"state":"ready"}"#));
+        assert!(Wait::new_http_body(&url, "ready").condition_met());
+        assert!(!Wait::new_http_body(&url, "starting").condition_met());
+        assert!((!Wait::new_http_body(&url, "starting")).condition_met());
+
+        let url = serve_http(http_response("503 Service Unavailable", &[], "not ready"));
+        assert!(Wait::new_http_body(&url, "not ready").condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_redirects() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        let target = serve_http(http_response("200 OK", &[], "home"));
+        let url = serve_http(http_response("302 Found", &[("Location", &target)], ""));
+
+        assert!(Wait::new_http_get(&url, 200).condition_met());
+        assert!(Wait::new_http_body(&url, "home").condition_met());
+
+        let w = Wait::new_http_get(&url, 302).with_follow_redirects(false);
+        assert!(w.condition_met());
+        assert!(!Wait::new_http_get(&url, 200)
+            .with_follow_redirects(false)
+            .condition_met());
+        assert!(!Wait::new_http_get(&url, 302).condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_basic_auth() {
+        use super::Wait;
+        use crate::testing::serve_http_basic_auth;
+
+        let url = serve_http_basic_auth("monitor", "hunter2");
+        assert!(Wait::new_http_get(&url, 401).condition_met());
+
+        let w = Wait::new_http_get(&url, 200).with_basic_auth("monitor", "hunter2");
+        assert!(w.condition_met());
+        assert!(!format!("{w:?}").contains("hunter2"));
+        assert!(!w.to_string().contains("hunter2"));
+
+        let w = Wait::new_http_get(&url, 200).with_basic_auth("monitor", "wrong");
+        assert!(!w.condition_met());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn http_json() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        let url = serve_http(http_response(
+            "200 OK",
+            &[],
+            r#"{"status":{"state":"running","replicas":3}}"#,
+        ));
+        assert!(Wait::new_http_json(&url, "/status/state", "running").condition_met());
+        assert!(Wait::new_http_json(&url, "/status/replicas", 3).condition_met());
+        assert!(!Wait::new_http_json(&url, "/status/state", "stopped").condition_met());
+        assert!((!Wait::new_http_json(&url, "/status/state", "stopped")).condition_met());
+        assert!(!Wait::new_http_json(&url, "/status/missing", "running").condition_met());
+        assert!((!Wait::new_http_json(&url, "/status/missing", "running")).condition_met());
+        assert_eq!(
+            Wait::new_http_json(&url, "/status/state", "running").to_string(),
+            format!("http_json({url:?}, \"/status/state\", \"running\")")
+        );
+
+        let url = serve_http(http_response("200 OK", &[], "running"));
+        assert!(!Wait::new_http_json(&url, "", "running").condition_met());
+        assert!(!(!Wait::new_http_json(&url, "", "running")).condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_header() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        let url = serve_http(http_response(
+            "503 Service Unavailable",
+            &[("X-Ready", "true")],
+            "",
+        ));
+        assert!(Wait::new_http_header(&url, "x-ready").condition_met());
+        assert!(Wait::new_http_header_equals(&url, "X-READY", "true").condition_met());
+        assert!(!Wait::new_http_header_equals(&url, "X-Ready", "TRUE").condition_met());
+        assert!(!Wait::new_http_header(&url, "X-Started").condition_met());
+        assert!((!Wait::new_http_header(&url, "X-Started")).condition_met());
+        assert!(!(!Wait::new_http_header(&url, "X-Ready")).condition_met());
+        assert_eq!(
+            Wait::new_http_header_equals(&url, "X-Ready", "true").to_string(),
+            format!("http_header({url:?}, \"X-Ready\", \"true\")")
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_head() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http, serve_http_recorded};
+
+        let (url, requests) = serve_http_recorded(http_response("204 No Content", &[], ""));
+        assert!(Wait::new_http_head(&url, 204).condition_met());
+        assert_eq!(requests.recv().unwrap(), "HEAD / HTTP/1.1");
+        assert!(!Wait::new_http_head(&url, 200).condition_met());
+        assert!((!Wait::new_http_head(&url, 200)).condition_met());
+
+        let url = serve_http(http_response("405 Method Not Allowed", &[], ""));
+        assert!(!Wait::new_http_head(&url, 200).condition_met());
+        assert!((!Wait::new_http_head(&url, 200)).condition_met());
+        assert_eq!(
+            Wait::new_http_head(&url, 200).to_string(),
+            format!("http_head({url:?}, 200)")
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_multi() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http, serve_nothing};
+        use crate::MultiMode;
+        use std::time::Duration;
+
+        let ok = serve_http(http_response("200 OK", &[], ""));
+        let ok2 = serve_http(http_response("200 OK", &[], ""));
+        let down = serve_http(http_response("503 Service Unavailable", &[], ""));
+
+        assert!(Wait::new_http_multi([&ok, &ok2], 200, MultiMode::All).condition_met());
+        assert!(!Wait::new_http_multi([&ok, &down], 200, MultiMode::All).condition_met());
+        assert!((!Wait::new_http_multi([&ok, &down], 200, MultiMode::All)).condition_met());
+        assert!(Wait::new_http_multi([&down, &ok], 200, MultiMode::Any).condition_met());
+        assert!(!Wait::new_http_multi([&down], 200, MultiMode::Any).condition_met());
+        assert!((!Wait::new_http_multi([&down], 200, MultiMode::Any)).condition_met());
+
+        // Any stops at the first match, so an unresponsive URL after it isn't requested
+        let hung = serve_nothing();
+        assert!(Wait::new_http_multi([&ok, &hung], 200, MultiMode::Any).condition_met());
+        let w = Wait::new_http_multi([&ok, &hung], 200, MultiMode::All)
+            .with_http_timeout(Duration::from_millis(100));
+        assert!(!w.condition_met());
+
+        let none: [&str; 0] = [];
+        assert!(Wait::new_http_multi(none, 200, MultiMode::All).condition_met());
+        assert!(!Wait::new_http_multi(none, 200, MultiMode::Any).condition_met());
+        assert_eq!(
+            Wait::new_http_multi([&ok], 200, MultiMode::Any).to_string(),
+            format!("http_multi(any, [{ok:?}], 200)")
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_latency() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http, serve_nothing};
+        use std::time::Duration;
+
+        let url = serve_http(http_response("200 OK", &[], "hello"));
+        assert!(Wait::new_http_latency(&url, Duration::from_secs(5)).condition_met());
+        assert!(!(!Wait::new_http_latency(&url, Duration::from_secs(5))).condition_met());
+        assert!((!Wait::new_http_latency(&url, Duration::ZERO)).condition_met());
+
+        // A fast response still has to be successful
+        let url = serve_http(http_response("500 Internal Server Error", &[], ""));
+        assert!(!Wait::new_http_latency(&url, Duration::from_secs(5)).condition_met());
+
+        // ...and one that never comes is over any budget
+        let url = serve_nothing();
+        let w = !Wait::new_http_latency(&url, Duration::from_secs(5))
+            .with_http_timeout(Duration::from_millis(100));
+        assert!(w.condition_met());
+        assert_eq!(
+            Wait::new_http_latency(&url, Duration::from_millis(200)).to_string(),
+            format!("http_latency({url:?}, 200ms)")
+        );
+    }
+
+    #[cfg(all(feature = "http", feature = "regex"))]
+    #[test]
+    fn http_body_regex() {
+        use super::Wait;
+        use crate::testing::{http_response, serve_http};
+
+        assert!(Wait::new_http_body_regex("http://localhost/", "(unclosed").is_err());
+
+        let url = serve_http(http_response("200 OK", &[], r#"{"replicas": 3}"#));
+        let w = Wait::new_http_body_regex(&url, r#""replicas":\s*[1-9]"#).unwrap();
+        assert!(w.condition_met());
+        assert!(!(!w).condition_met());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_timeout() {
+        use super::Wait;
+        use std::time::{Duration, Instant};
+
+        let url = crate::testing::serve_nothing();
+        let w = Wait::new_http_get(url, 200).with_http_timeout(Duration::from_millis(100));
+
+        let start = Instant::now();
+        assert!(!w.condition_met());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_get_unreachable() {
+        use super::Wait;
+
+        // Bind then drop a listener to find a port that (very likely) refuses connections
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let url = format!("http://{addr}/");
+        assert!(!Wait::new_http_get(&url, 200).condition_met());
+        assert!((!Wait::new_http_get(&url, 200)).condition_met());
+    }
+
+    #[test]
+    fn tcp_connect() {
+        use super::Wait;
+        use std::{
+            net::TcpListener,
+            time::{Duration, Instant},
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        assert!(Wait::new_tcp_connect(&host).condition_met());
+        assert!(Wait::try_new_tcp_connect(&host).unwrap().condition_met());
+        assert!(Wait::try_new_tcp_connect("localhost").is_err());
+
+        // Nothing is listening on the port now, so connecting is refused right away
+        drop(listener);
+        let w = Wait::new_tcp_connect(&host).with_tcp_timeout(Duration::from_millis(200));
+        let start = Instant::now();
+        assert!(!w.condition_met());
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!((!w).condition_met());
+    }
+
+    #[cfg(all(debug_assertions, feature = "http"))]
+    #[test]
+    #[should_panic(expected = "doesn't make HTTP requests")]
+    fn http_timeout_other() {
+        use super::Wait;
+
+        let _ = Wait::new_tcp_connect("localhost:80").with_http_timeout(std::time::Duration::ZERO);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "doesn't make TCP connections")]
+    fn tcp_timeout_other() {
+        use super::Wait;
+
+        let _ = Wait::new_file_exists("foo.txt").with_tcp_timeout(std::time::Duration::ZERO);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pid() {
+        use super::Wait;
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .unwrap();
+        let w = Wait::new_pid(child.id());
+        assert!(!w.condition_met());
+        assert!((!w.clone()).condition_met());
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(w.condition_met());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn process_named() {
+        use super::Wait;
+        use std::time::Duration;
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .unwrap();
+        // The child may not have called exec yet, so give it a moment
+        let w = Wait::new_process_named("sleep");
+        assert!(w.wait_timeout(Duration::from_millis(10), Duration::from_secs(5)));
+        assert!(!Wait::new_process_named("slee").condition_met());
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let name = "no-such-process-waitforit";
+        assert!((!Wait::new_process_named(name)).condition_met());
+    }
+
+    #[test]
+    fn elapsed_boundary() {
+        use super::Wait;
+        use std::time::{Duration, Instant};
+
+        let end = Instant::now();
+        let nanosecond = Duration::from_nanos(1);
+        assert!(!super::elapsed(end, end - nanosecond));
+        assert!(super::elapsed(end, end));
+        assert!(super::elapsed(end, end + nanosecond));
+
+        let past = Wait::new_elapsed(Instant::now());
+        assert!(past.condition_met());
+        assert!(!(!past).condition_met());
+
+        let future = Wait::new_elapsed_from_duration(Duration::from_secs(60));
+        assert!(!future.condition_met());
+        assert!((!future).condition_met());
+    }
+
+    #[test]
+    fn elapsed_from_str() {
+        use super::{ParseDurationError, Wait};
+        use std::time::{Duration, Instant};
+
+        assert!(Wait::new_elapsed_from_str("0s").unwrap().condition_met());
+        let soon = Instant::now() + Duration::from_secs(11_000);
+        let future = Wait::new_elapsed_from_str("3h10m").unwrap();
+        assert!(!future.condition_met());
+        assert!(matches!(future, Wait::Elapsed { end_instant, .. } if end_instant > soon));
+        assert!(matches!(
+            Wait::new_elapsed_from_str(""),
+            Err(ParseDurationError::Empty)
+        ));
+        assert!(matches!(
+            Wait::new_elapsed_from_str("10x"),
+            Err(ParseDurationError::UnexpectedChar('x'))
+        ));
+    }
+
+    #[test]
+    fn until() {
+        use super::Wait;
+        use std::time::{Duration, SystemTime};
+
+        let past = Wait::new_until(SystemTime::now() - Duration::from_secs(1));
+        let future = Wait::new_until(SystemTime::now() + Duration::from_secs(60));
+        assert!(past.condition_met());
+        assert!(!future.condition_met());
+        assert!((!future).condition_met());
+
+        let soon = Wait::new_until(SystemTime::now() + Duration::from_millis(50));
+        assert!(soon.wait_timeout(Duration::from_millis(10), Duration::from_secs(5)));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn until_chrono() {
+        use super::Wait;
+
+        let date = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap();
+        assert!(Wait::new_until(date.to_utc()).condition_met());
+        assert!(!Wait::new_until(chrono::Utc::now() + chrono::Duration::hours(1)).condition_met());
+    }
+
+    #[cfg(feature = "cron")]
+    #[test]
+    fn schedule() {
+        use super::Wait;
+        use std::time::Duration;
+
+        let every_second = Wait::new_schedule("* * * * * *").unwrap();
+        let before = !every_second.clone();
+        assert!(!every_second.condition_met());
+        assert!(before.condition_met());
+        assert!(every_second.wait_timeout(Duration::from_millis(10), Duration::from_secs(3)));
+        assert!(!before.condition_met());
+
+        assert!(!Wait::new_schedule("0 0 0 1 1 * 2000")
+            .unwrap()
+            .condition_met());
+        assert!(Wait::new_schedule("not cron").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn time_of_day() {
+        use super::{time_of_day_reached, SyncCell, Wait};
+        use chrono::NaiveDate;
+
+        let at = |day, hour, minute| {
+            NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+        };
+        let fired = SyncCell::new(None);
+        assert!(!time_of_day_reached(at(1, 8, 59), 9, 0, &fired));
+        assert!(time_of_day_reached(at(1, 9, 0), 9, 0, &fired));
+        assert!(!time_of_day_reached(at(1, 9, 1), 9, 0, &fired));
+        assert!(!time_of_day_reached(at(1, 23, 59), 9, 0, &fired));
+
+        // The next day, it waits until the time again
+        assert!(!time_of_day_reached(at(2, 0, 0), 9, 0, &fired));
+        assert!(time_of_day_reached(at(2, 12, 30), 9, 0, &fired));
+        fired.set(None);
+        assert!(time_of_day_reached(at(2, 12, 31), 9, 0, &fired));
+
+        assert!(Wait::new_time_of_day(24, 0).is_none());
+        assert!(Wait::new_time_of_day(0, 60).is_none());
+        let midnight = Wait::new_time_of_day(0, 0).unwrap();
+        assert!(midnight.condition_met());
+        assert!(!midnight.condition_met());
+        midnight.reset();
+        assert!(midnight.condition_met());
+        assert_eq!(midnight.to_string(), "time_of_day(00:00)");
+    }
+
+    #[test]
+    fn dirs() {
+        use super::Wait;
+
+        let dir = std::env::temp_dir().join(format!("waitforit-dir-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let exists = Wait::new_dir_exists(&dir);
+        let non_empty = Wait::new_dir_non_empty(&dir);
+        assert!(!exists.condition_met());
+        assert!(!non_empty.condition_met());
+
+        std::fs::create_dir(&dir).unwrap();
+        assert!(exists.condition_met());
+        assert!(!non_empty.condition_met());
+        assert!((!non_empty.clone()).condition_met());
+
+        let file = dir.join("file");
+        std::fs::write(&file, b"").unwrap();
+        assert!(non_empty.condition_met());
+
+        // A file isn't a directory
+        assert!(!Wait::new_dir_exists(&file).condition_met());
+        assert!(!Wait::new_dir_non_empty(&file).condition_met());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "glob")]
+    #[test]
+    fn glob_exists() {
+        use super::Wait;
+
+        let dir = std::env::temp_dir().join(format!("waitforit-glob-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::write(dir.join("report.txt"), b"").unwrap();
+
+        let pattern = dir.join("report-*.csv");
+        let w = Wait::new_glob_exists(pattern.to_str().unwrap()).unwrap();
+        assert!(!w.condition_met());
+        assert!((!w.clone()).condition_met());
+
+        std::fs::write(dir.join("report-2024.csv"), b"").unwrap();
+        assert!(w.condition_met());
+
+        assert!(Wait::new_glob_exists("[").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dir_file_count() {
+        use super::Wait;
+
+        let dir = std::env::temp_dir().join(format!("waitforit-count-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir(&dir).unwrap();
+
+        let two = Wait::new_dir_file_count(&dir, 2);
+        let two_json = Wait::new_dir_file_count(&dir, 2).with_extension(".json");
+        let two_entries = Wait::new_dir_file_count(&dir, 2).with_files_only(false);
+
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        std::fs::write(dir.join(".hidden.json"), b"").unwrap();
+        std::fs::write(dir.join("a.json"), b"").unwrap();
+        assert!(!two.condition_met());
+        assert!((!two.clone()).condition_met());
+        assert!(!two_json.condition_met());
+        assert!(two_entries.condition_met());
+
+        std::fs::write(dir.join("b.txt"), b"").unwrap();
+        assert!(two.condition_met());
+        assert!(!two_json.condition_met());
+
+        std::fs::write(dir.join("c.json"), b"").unwrap();
+        assert!(two_json.condition_met());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(!two.condition_met());
+    }
+
+    #[test]
+    fn file_size_change() {
+        use super::{SizeChange, Wait};
+
+        let path = std::env::temp_dir().join(format!("waitforit-change-{}", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+
+        let any = Wait::new_file_size(&path);
+        let grow = Wait::new_file_size_change(&path, SizeChange::Grow);
+        let shrink = Wait::new_file_size_change(&path, SizeChange::Shrink);
+        let not_grow = !grow.clone();
+        for w in [&any, &grow, &shrink, &not_grow] {
+            // The first check only records the size
+            assert!(!w.condition_met());
+        }
+
+        std::fs::write(&path, b"a").unwrap();
+        assert!(any.condition_met());
+        assert!(!grow.condition_met());
+        assert!(shrink.condition_met());
+        assert!(not_grow.condition_met());
+
+        std::fs::write(&path, b"abcdef").unwrap();
+        assert!(grow.condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reset() {
+        use super::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-reset-{}", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+
+        let w = Wait::new_file_size(&path);
+        assert!(!w.condition_met());
+        std::fs::write(&path, b"abcdef").unwrap();
+        assert!(w.condition_met());
+
+        // Without a reset, the old size would still be compared against
+        w.reset();
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        std::fs::write(&path, b"abcdefghi").unwrap();
+        assert!(w.condition_met());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn file_update_since() {
+        use super::Wait;
+        use std::time::{Duration, SystemTime};
+
+        let path = std::env::temp_dir().join(format!("waitforit-since-{}", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+
+        let recent = Wait::new_file_update_since(&path, Duration::from_secs(3600));
+        let idle = !recent.clone();
+        assert!(recent.condition_met());
+        assert!(!idle.condition_met());
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(7200))
+            .unwrap();
+        assert!(!recent.condition_met());
+        assert!(idle.condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn wait_counted() {
+        use super::Wait;
+        use std::time::Duration;
+
+        assert_eq!(Wait::new_always().wait_counted(Duration::ZERO), 1);
+
+        let mut checks = 0;
+        let w = Wait::new_custom_stateful(move || {
+            checks += 1;
+            checks == 5
+        });
+        assert_eq!(w.wait_counted(Duration::ZERO), 5);
+    }
+
+    #[test]
+    fn modified_since() {
+        use super::Wait;
+        use std::time::{Duration, SystemTime};
+
+        let path = std::env::temp_dir().join(format!("waitforit-modified-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let hour_ago = SystemTime::now() - Duration::from_secs(3600);
+        let w = Wait::new_modified_since(&path, hour_ago);
+        assert!(!w.condition_met());
+        assert!(!(!w.clone()).condition_met());
+
+        std::fs::write(&path, b"").unwrap();
+        assert!(w.condition_met());
+        assert!(!(!w.clone()).condition_met());
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(hour_ago).unwrap();
+        assert!(!w.condition_met());
+        assert!((!w).condition_met());
+        assert!(!Wait::new_modified_since_now(&path).condition_met());
+
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert!(Wait::new_modified_since_now(&path).condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_empty() {
+        use super::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-empty-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let w = Wait::new_file_empty(&path);
+        assert!(!w.condition_met());
+        assert!(!(!w.clone()).condition_met());
+
+        std::fs::write(&path, b"locked").unwrap();
+        assert!(!w.condition_met());
+        assert!((!w.clone()).condition_met());
+
+        std::fs::write(&path, b"").unwrap();
+        assert!(w.condition_met());
+        assert!(!(!w).condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_mode() {
+        use super::Wait;
+        use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+
+        let path = std::env::temp_dir().join(format!("waitforit-mode-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let executable = Wait::new_file_mode(&path, 0o111, 0o111);
+        assert!(!executable.condition_met());
+        assert!(!(!executable.clone()).condition_met());
+
+        std::fs::write(&path, b"#!/bin/sh").unwrap();
+        std::fs::set_permissions(&path, Permissions::from_mode(0o644)).unwrap();
+        assert!(!executable.condition_met());
+        assert!((!executable.clone()).condition_met());
+
+        std::fs::set_permissions(&path, Permissions::from_mode(0o755)).unwrap();
+        assert!(executable.condition_met());
+        assert!(Wait::new_file_mode(&path, 0o002, 0).condition_met());
+        assert_eq!(
+            executable.to_string(),
+            format!("file_mode({path:?}, 0o111, 0o111)")
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_size_lifecycle() {
+        use super::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-lifecycle-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // By default, a missing file counts as "stable"
+        let stable = !Wait::new_file_size(&path);
+        assert!(stable.condition_met());
+
+        let stable = stable.with_require_exists(true);
+        let changed = Wait::new_file_size(&path).with_require_exists(true);
+        assert!(!stable.condition_met());
+        assert!(!changed.condition_met());
+
+        // Created: the first size is recorded
+        std::fs::write(&path, b"").unwrap();
+        assert!(!stable.condition_met());
+        assert!(!changed.condition_met());
+
+        // Growing
+        std::fs::write(&path, b"abc").unwrap();
+        assert!(!stable.condition_met());
+        assert!(changed.condition_met());
+
+        // Stabilized
+        assert!(stable.condition_met());
+
+        // Deleted, then recreated with the same size, which is tracked afresh
+        std::fs::remove_file(&path).unwrap();
+        assert!(!stable.condition_met());
+        std::fs::write(&path, b"abc").unwrap();
+        assert!(!stable.condition_met());
+        assert!(stable.condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_update_lifecycle() {
+        use super::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-update-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(Wait::new_file_update(&path).condition_met());
+
+        let updated = Wait::new_file_update(&path).with_require_exists(true);
+        let idle = !updated.clone();
+        assert!(!updated.condition_met());
+        assert!(!idle.condition_met());
+
+        std::fs::write(&path, b"a").unwrap();
+        assert!(!updated.condition_met());
+        assert!(!idle.condition_met());
+        assert!(idle.condition_met());
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(updated.condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_hash_stable() {
+        use super::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-hash-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let stable = Wait::new_file_hash_stable(&path);
+        let changed = !stable.clone();
+        assert!(!stable.condition_met());
+        assert!(!changed.condition_met());
+
+        std::fs::write(&path, b"abc").unwrap();
+        assert!(!stable.condition_met());
+        assert!(!changed.condition_met());
+
+        // Same size, different contents
+        std::fs::write(&path, b"xyz").unwrap();
+        assert!(!stable.condition_met());
+        assert!(changed.condition_met());
+
+        assert!(stable.condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_size_at_least() {
+        use super::Wait;
+
+        let path = std::env::temp_dir().join(format!("waitforit-at-least-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let w = Wait::new_file_size_at_least(&path, 4);
+        assert!(!w.condition_met());
+        assert!((!w.clone()).condition_met());
+
+        std::fs::write(&path, b"abc").unwrap();
+        assert!(!w.condition_met());
+        std::fs::write(&path, b"abcd").unwrap();
+        assert!(w.condition_met());
+        assert!(!(!w).condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_contains() {
+        use super::Wait;
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("waitforit-contains-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let w = Wait::new_file_contains(&path, "started");
+        assert!(!w.condition_met());
+        assert!((!w.clone()).condition_met());
+
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(b"starting up\nstil").unwrap();
+        assert!(!w.condition_met());
+        let Wait::FileContains { offset, .. } = &w else {
+            unreachable!()
+        };
+        assert_eq!(offset.get(), 12);
+
+        // Completing the partial line causes it to be read again
+        file.write_all(b"l starting\nserver started\n").unwrap();
+        assert!(w.condition_met());
+        assert!(w.condition_met());
+        assert_eq!(offset.get(), 27);
+
+        // Truncation rewinds to the start
+        std::fs::write(&path, b"restarting\n").unwrap();
+        assert!(!w.condition_met());
+        assert_eq!(offset.get(), 11);
+        std::fs::write(&path, b"started\n").unwrap();
+        assert!(w.condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<super::Wait>();
+        assert_send_sync::<crate::Waits>();
+
+        let w = std::sync::Arc::new(super::Wait::new_file_size("."));
+        let w2 = w.clone();
+        std::thread::spawn(move || w2.condition_met())
+            .join()
+            .unwrap();
+        w.condition_met();
+    }
+
+    #[test]
+    fn wait_with_callback() {
+        use super::Wait;
+        use std::{
+            sync::{
+                atomic::{AtomicU32, Ordering},
+                Arc,
+            },
+            time::Duration,
+        };
+
+        let checks = Arc::new(AtomicU32::new(0));
+        let c = checks.clone();
+        let w = Wait::new_custom_closure(move || c.fetch_add(1, Ordering::SeqCst) == 2);
+
+        let mut polls = Vec::new();
+        w.wait_with_callback(Duration::from_millis(1), |attempt, met| {
+            polls.push((attempt, met))
+        });
+        assert_eq!(polls, [(0, false), (1, false), (2, true)]);
+    }
+
+    #[test]
+    fn throttled() {
+        use super::Wait;
+        use std::{
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            time::Duration,
+        };
+
+        let checks = Arc::new(AtomicUsize::new(0));
+        let c = checks.clone();
+        let inner = Wait::new_custom_closure(move || c.fetch_add(1, Ordering::SeqCst) > 0);
+
+        let w = Wait::new_throttled(inner.clone(), Duration::from_secs(3600));
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        assert!((!w.clone()).condition_met());
+        assert_eq!(checks.load(Ordering::SeqCst), 1);
+
+        let w = Wait::new_throttled(inner, Duration::from_millis(20));
+        assert!(w.condition_met());
+        assert!(w.wait_timeout(Duration::from_millis(1), Duration::from_secs(5)));
+        assert_eq!(checks.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn env_var() {
+        use super::Wait;
+
+        let name = "WAITFORIT_TEST_ENV_VAR";
+        let set = Wait::new_env_var(name);
+        let equals = Wait::new_env_var_equals(name, "ready");
+        assert!(!set.condition_met());
+        assert!((!set.clone()).condition_met());
+        assert!(!equals.condition_met());
+
+        std::env::set_var(name, "starting");
+        assert!(set.condition_met());
+        assert!(!equals.condition_met());
+        assert!((!equals.clone()).condition_met());
+
+        std::env::set_var(name, "ready");
+        assert!(equals.condition_met());
+        std::env::remove_var(name);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command() {
+        use super::Wait;
+
+        assert!(Wait::new_command("true", Vec::<String>::new(), 0).condition_met());
+        assert!(Wait::new_command("sh", ["-c", "exit 3"], 3).condition_met());
+        assert!((!Wait::new_command("sh", ["-c", "exit 3"], 0)).condition_met());
+
+        // A missing program never satisfies the condition, negated or not
+        let missing = Wait::new_command("no-such-program-waitforit", Vec::<String>::new(), 0);
+        assert!(!missing.condition_met());
+        assert!(!(!missing).condition_met());
+    }
+
+    #[test]
+    fn dns_resolves() {
+        use super::Wait;
+
+        assert!(Wait::new_dns_resolves("localhost").condition_met());
+        assert!(Wait::new_dns_resolves("localhost:80").condition_met());
+        assert!(Wait::new_dns_resolves("127.0.0.1").condition_met());
+        assert!(!Wait::new_dns_resolves("nonexistent.invalid").condition_met());
+        assert!((!Wait::new_dns_resolves("nonexistent.invalid")).condition_met());
+    }
+
+    #[test]
+    fn port_free() {
+        use super::Wait;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        assert!(!Wait::new_port_free(&addr).condition_met());
+        assert!((!Wait::new_port_free(&addr)).condition_met());
 
-                let is_recently_updated = since_last_update < *trigger_duration;
+        drop(listener);
+        assert!(Wait::new_port_free(&addr).condition_met());
+    }
 
-                // The condition is met if:
-                // 1. we're looking for a recent update and it was recently updated, or
-                // 2. it's not recently updated and we're not looking for it, which means
-                // condition_met := is_recently_updated XOR not_negating
-                is_recently_updated ^ not
+    #[test]
+    fn udp_host() {
+        use super::Wait;
+        use std::net::UdpSocket;
+
+        assert!(Wait::new_udp_host("localhost", "ping", true).is_err());
+
+        // An echo server
+        let echo = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let echo_host = echo.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            let mut buf = [0; 64];
+            while let Ok((n, from)) = echo.recv_from(&mut buf) {
+                let _ = echo.send_to(&buf[..n], from);
             }
+        });
+        assert!(Wait::new_udp_host(&echo_host, "ping", true)
+            .unwrap()
+            .condition_met());
 
-            Wait::FileSize {
-                not,
-                path,
-                size_bytes: bytes,
-            } => {
-                match (bytes.get(), get_file_size(path)) {
-                    // Can't get the file size. This is probably due to file non-existence,
-                    // so we'll assume the condition is met
-                    (_, None) => true,
-                    // Sizes are different when not negating -- condition is met
-                    (Some(prev), Some(curr)) if !*not && prev != curr => true,
-                    // Size hasn't changed when negating -- condition is met
-                    (Some(prev), Some(curr)) if *not && prev == curr => true,
-                    // First time or subsequent with changing values - save the (new) size and try again
-                    (_, curr) => {
-                        bytes.set(curr);
-                        false
-                    }
-                }
+        // A server that never replies
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let silent_host = silent.local_addr().unwrap().to_string();
+        let w = Wait::new_udp_host(&silent_host, "ping", true)
+            .unwrap()
+            .with_udp_timeout(std::time::Duration::from_millis(50));
+        assert!(!w.condition_met());
+        assert!(Wait::new_udp_host(&silent_host, "ping", false)
+            .unwrap()
+            .condition_met());
+    }
+
+    #[test]
+    fn udp_reachable_any() {
+        use super::udp_reachable_any;
+        use std::{net::UdpSocket, time::Duration};
+
+        let echo = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let echo_addr = echo.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buf = [0; 64];
+            while let Ok((n, from)) = echo.recv_from(&mut buf) {
+                let _ = echo.send_to(&buf[..n], from);
             }
+        });
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let silent_addr = silent.local_addr().unwrap();
 
-            Wait::Custom { f, not } => {
-                if *not {
-                    !(f)()
-                } else {
-                    (f)()
-                }
-            } //Wait::Pid { pid: _ } => todo!(),
+        let timeout = Duration::from_millis(50);
+        assert!(udp_reachable_any([silent_addr, echo_addr], b"ping", true, timeout).is_ok());
+        assert!(udp_reachable_any([echo_addr, silent_addr], b"ping", true, timeout).is_ok());
+        assert!(udp_reachable_any([silent_addr], b"ping", true, timeout).is_err());
+        assert_eq!(
+            udp_reachable_any([], b"ping", true, timeout)
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn parse_duration() {
+        use super::{parse_duration, ParseDurationError};
+        use std::time::Duration;
+
+        assert_eq!(parse_duration("3h10m"), Ok(Duration::from_secs(11400)));
+        assert_eq!(parse_duration("1d1s"), Ok(Duration::from_secs(86401)));
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1w2d"), Ok(Duration::from_secs(9 * 86400)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1m500ms"), Ok(Duration::from_millis(60500)));
+        assert_eq!(parse_duration("2m3"), Ok(Duration::from_secs(123)));
+        assert_eq!(parse_duration("0"), Ok(Duration::ZERO));
+        assert_eq!(parse_duration("0s"), Ok(Duration::ZERO));
+        assert_eq!(parse_duration(""), Err(ParseDurationError::Empty));
+        assert_eq!(
+            parse_duration("h"),
+            Err(ParseDurationError::MissingNumber('h'))
+        );
+        assert_eq!(
+            parse_duration("ms"),
+            Err(ParseDurationError::MissingNumber('m'))
+        );
+        assert_eq!(
+            parse_duration("1hm"),
+            Err(ParseDurationError::MissingNumber('m'))
+        );
+        assert_eq!(
+            parse_duration(" 1s"),
+            Err(ParseDurationError::UnexpectedChar(' '))
+        );
+        assert_eq!(
+            parse_duration("1.5s"),
+            Err(ParseDurationError::UnexpectedChar('.'))
+        );
+        assert_eq!(
+            parse_duration("10x"),
+            Err(ParseDurationError::UnexpectedChar('x'))
+        );
+        assert_eq!(
+            parse_duration("100000d"),
+            Ok(Duration::from_secs(8_640_000_000))
+        );
+        assert_eq!(
+            parse_duration("100000000000000w"),
+            Err(ParseDurationError::Overflow)
+        );
+        assert_eq!(
+            parse_duration("99999999999999999999s"),
+            Err(ParseDurationError::Overflow)
+        );
+        assert_eq!(
+            parse_duration("18446744073709551615s1s"),
+            Err(ParseDurationError::Overflow)
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn parse_http_get() {
+        use super::{parse_http_get, HttpArgError};
+
+        assert_eq!(
+            parse_http_get("418,https://x"),
+            Ok((418, "https://x/".to_string()))
+        );
+        assert_eq!(
+            parse_http_get("https://x"),
+            Ok((200, "https://x/".to_string()))
+        );
+        assert_eq!(
+            parse_http_get("https://x/?a=1,2"),
+            Ok((200, "https://x/?a=1,2".to_string()))
+        );
+        assert_eq!(
+            parse_http_get("abc,https://x"),
+            Err(HttpArgError::InvalidStatus("abc".to_string()))
+        );
+        assert_eq!(
+            parse_http_get("20,https://x"),
+            Err(HttpArgError::StatusOutOfRange("20".to_string()))
+        );
+        assert_eq!(
+            parse_http_get("2000,https://x"),
+            Err(HttpArgError::StatusOutOfRange("2000".to_string()))
+        );
+        assert_eq!(
+            parse_http_get("99999999,https://x"),
+            Err(HttpArgError::StatusOutOfRange("99999999".to_string()))
+        );
+    }
+
+    #[test]
+    fn display() {
+        use super::{SizeChange, Wait};
+        use std::time::Duration;
+
+        let w = Wait::new_elapsed_from_duration(Duration::from_secs(5400));
+        assert_eq!(w.to_string(), "elapsed(1h30m)");
+        let w = !Wait::new_elapsed_from_duration(Duration::from_millis(250));
+        assert!(w.to_string().starts_with("NOT elapsed("));
+        assert!(w.to_string().ends_with("ms)"));
+
+        assert_eq!(
+            Wait::new_file_exists("foo.txt").to_string(),
+            r#"exists("foo.txt")"#
+        );
+        assert_eq!(
+            (!Wait::new_tcp_connect("localhost:80")).to_string(),
+            r#"NOT tcp("localhost:80")"#
+        );
+        assert_eq!(
+            Wait::new_file_size_change("log.txt", SizeChange::Grow).to_string(),
+            r#"file_size_grew("log.txt")"#
+        );
+        assert_eq!(
+            Wait::new_command("test", ["-e", "foo"], 0).to_string(),
+            r#"command("test", ["-e", "foo"], 0)"#
+        );
+        assert_eq!(Wait::new_custom(|| true).to_string(), "custom()");
+        assert_eq!(
+            Wait::new_throttled(
+                Wait::new_dns_resolves("example.com"),
+                Duration::from_secs(30)
+            )
+            .to_string(),
+            r#"throttled(dns("example.com"), 30s)"#
+        );
+    }
+
+    #[test]
+    fn format_duration() {
+        use super::{format_duration, parse_duration};
+        use std::time::Duration;
+
+        assert_eq!(format_duration(Duration::ZERO), "0ms");
+        assert_eq!(format_duration(Duration::from_millis(999)), "999ms");
+        assert_eq!(format_duration(Duration::from_millis(1499)), "1s");
+        assert_eq!(format_duration(Duration::from_millis(9999)), "10s");
+        assert_eq!(format_duration(Duration::from_secs(90061)), "1d1h1m1s");
+        assert_eq!(format_duration(Duration::from_secs(7200)), "2h");
+
+        for secs in [1, 59, 60, 3601, 86400, 1_000_000] {
+            let d = Duration::from_secs(secs);
+            assert_eq!(parse_duration(&format_duration(d)), Ok(d));
         }
     }
 
-    /// Wait for the completion of this condition. This will block the thread.
-    pub fn wait(&self, interval: Duration) {
-        loop {
-            let start = Instant::now();
-            if self.condition_met() {
-                return;
+    #[test]
+    fn file_unlocked() {
+        use super::Wait;
+        use std::fs::File;
+
+        let path = std::env::temp_dir().join(format!("waitforit-lock-{}", std::process::id()));
+        assert!(!Wait::new_file_unlocked(&path).condition_met());
+
+        let file = File::create(&path).unwrap();
+        assert!(Wait::new_file_unlocked(&path).condition_met());
+
+        file.lock().unwrap();
+        assert!(!Wait::new_file_unlocked(&path).condition_met());
+        assert!((!Wait::new_file_unlocked(&path)).condition_met());
+
+        file.unlock().unwrap();
+        assert!(Wait::new_file_unlocked(&path).condition_met());
+
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn disk_free() {
+        use super::Wait;
+
+        let dir = std::env::temp_dir();
+        assert!(Wait::new_disk_free(&dir, 0).condition_met());
+        assert!(Wait::new_disk_free(&dir, 1).condition_met());
+        assert!(!Wait::new_disk_free(&dir, u64::MAX).condition_met());
+        assert!((!Wait::new_disk_free(&dir, u64::MAX)).condition_met());
+
+        // The space can't be queried, so neither is met
+        let missing = dir.join("waitforit-nonexistent/file");
+        assert!(!Wait::new_disk_free(&missing, 0).condition_met());
+        assert!(!(!Wait::new_disk_free(&missing, 0)).condition_met());
+    }
+
+    #[cfg(any(target_os = "linux", windows, feature = "sysinfo"))]
+    #[test]
+    fn memory_free() {
+        use super::Wait;
+
+        assert!(Wait::new_memory_free(1).condition_met());
+        assert!(!Wait::new_memory_free(u64::MAX).condition_met());
+        assert!((!Wait::new_memory_free(u64::MAX)).condition_met());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_below() {
+        use super::{LoadWindow, Wait};
+
+        for window in [
+            LoadWindow::OneMinute,
+            LoadWindow::FiveMinutes,
+            LoadWindow::FifteenMinutes,
+        ] {
+            assert!(Wait::new_load_below(f64::MAX, window).condition_met());
+            assert!(!Wait::new_load_below(0.0, window).condition_met());
+            assert!((!Wait::new_load_below(0.0, window)).condition_met());
+        }
+        assert_eq!(
+            Wait::new_load_below(2.5, LoadWindow::FiveMinutes).to_string(),
+            "load_below(2.5, 5m)"
+        );
+    }
+
+    #[test]
+    fn tcp_banner() {
+        use super::Wait;
+        use std::{
+            io::Write,
+            net::TcpListener,
+            time::{Duration, Instant},
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                // Every other connection gets nothing at all
+                if i % 2 == 0 {
+                    let _ = stream.write_all(b"SSH-2.0-OpenSSH_9.6\r\nmore");
+                } else {
+                    std::thread::spawn(move || {
+                        std::thread::sleep(Duration::from_secs(2));
+                        drop(stream);
+                    });
+                }
             }
+        });
+
+        assert!(Wait::new_tcp_banner(&host, "SSH-2.0").condition_met());
+        let start = Instant::now();
+        let silent =
+            Wait::new_tcp_banner(&host, "SSH-2.0").with_tcp_timeout(Duration::from_millis(50));
+        assert!(!silent.condition_met());
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        assert!(!Wait::new_tcp_banner(&host, "220").condition_met());
+        assert!((!Wait::new_tcp_banner("127.0.0.1:1", "220")).condition_met());
+    }
 
-            let loop_time = start.elapsed();
-            if interval > loop_time {
-                std::thread::sleep(interval - loop_time);
+    #[cfg(feature = "tls")]
+    #[test]
+    fn tls_cert_valid() {
+        use super::Wait;
+        use std::{io::Write, net::TcpListener, time::Duration};
+
+        // Something that isn't a TLS server
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let host = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let _ = stream.unwrap().write_all(b"SSH-2.0-OpenSSH_9.6\r\n");
             }
-        }
+        });
+
+        let w = Wait::new_tls_cert_valid(&host, Duration::ZERO)
+            .with_tcp_timeout(Duration::from_millis(500));
+        assert!(!w.condition_met());
+        assert!(!(!w.clone()).condition_met());
+        assert_eq!(w.to_string(), format!("tls_cert({host:?}, 0ms)"));
+
+        // Nothing listening at all
+        let w = Wait::new_tls_cert_valid("127.0.0.1:1", Duration::from_secs(86400));
+        assert!(!w.condition_met());
+        assert!(!(!w).condition_met());
     }
-}
 
-impl std::ops::Not for Wait {
-    type Output = Self;
+    #[cfg(all(unix, feature = "signal"))]
+    #[test]
+    fn signal() {
+        use super::Wait;
+        use signal_hook::consts::{SIGKILL, SIGUSR1};
 
-    fn not(mut self) -> Self::Output {
-        let not = match &mut self {
-            Wait::Elapsed { not, .. } => not,
-            Wait::Exists { not, .. } => not,
-            Wait::HttpGet { not, .. } => not,
-            Wait::TcpHost { not, .. } => not,
-            Wait::Update { not, .. } => not,
-            Wait::UpdateSince { not, .. } => not,
-            Wait::FileSize { not, .. } => not,
-            Wait::Custom { not, .. } => not,
+        assert!(Wait::new_signal(SIGKILL).is_err());
+
+        let w = Wait::new_signal(SIGUSR1).unwrap();
+        let not_yet = !w.clone();
+        assert!(!w.condition_met());
+        assert!(not_yet.condition_met());
+        assert_eq!(w.to_string(), format!("signal({SIGUSR1})"));
+
+        signal_hook::low_level::raise(SIGUSR1).unwrap();
+        assert!(w.condition_met());
+        assert!(!not_yet.condition_met());
+
+        w.reset();
+        assert!(!w.condition_met());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket() {
+        use super::Wait;
+        use std::os::unix::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("waitforit-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert!(!Wait::new_unix_socket(&path).condition_met());
+
+        let listener = UnixListener::bind(&path).unwrap();
+        assert!(Wait::new_unix_socket(&path).condition_met());
+
+        // The socket file is left behind, but nothing is listening
+        drop(listener);
+        assert!(path.exists());
+        assert!(!Wait::new_unix_socket(&path).condition_met());
+        assert!((!Wait::new_unix_socket(&path)).condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fifo_ready() {
+        use super::Wait;
+        use std::{
+            ffi::CString,
+            io::Write,
+            os::unix::{ffi::OsStrExt, fs::OpenOptionsExt},
         };
 
-        *not = !*not;
+        let path = std::env::temp_dir().join(format!("waitforit-{}.fifo", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert!(!Wait::new_fifo_ready(&path).condition_met());
 
-        self
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
 
-        /*
-        match self {
-            Wait::Elapsed { end_instant, not } => Wait::Elapsed {
-                end_instant,
-                not: !not,
-            },
-            Wait::Exists { not, path } => Wait::Exists { not: !not, path },
-            Wait::HttpGet { not, url, status } => Wait::HttpGet {
-                not: !not,
-                url,
-                status,
-            },
-            Wait::TcpHost { not, host } => Wait::TcpHost { not: !not, host },
-            Wait::Update {
-                not,
-                path,
-                last_update,
-            } => Wait::Update {
-                not: !not,
-                path,
-                last_update,
-            },
-            Wait::UpdateSince {
-                not,
-                path,
-                trigger_duration,
-            } => Wait::UpdateSince {
-                not: !not,
-                path,
-                trigger_duration,
-            },
-            Wait::FileSize {
-                not,
-                path,
-                size_bytes,
-            } => Wait::FileSize {
-                not: !not,
-                path,
-                size_bytes,
-            },
-            Wait::Custom { f, not } => Wait::Custom { f, not: !not },
-        }
-        */
+        // No writer yet
+        assert!(!Wait::new_fifo_ready(&path).condition_met());
+        assert!((!Wait::new_fifo_ready(&path)).condition_met());
+
+        // Hold a reader open so that the writer can open it and data stays buffered
+        let _reader = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)
+            .unwrap();
+        let mut writer = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        assert!(!Wait::new_fifo_ready(&path).condition_met());
+
+        writer.write_all(b"data").unwrap();
+        assert!(Wait::new_fifo_ready(&path).condition_met());
+        // Checking doesn't consume anything
+        assert!(Wait::new_fifo_ready(&path).condition_met());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(!Wait::new_fifo_ready(std::env::temp_dir()).condition_met());
     }
-}
 
-fn get_modified_time(path: &Path) -> Option<SystemTime> {
-    let meta = path.metadata().ok()?;
-    meta.modified().ok()
-}
+    #[test]
+    fn always_never() {
+        use super::Wait;
 
-fn get_file_size(path: &Path) -> Option<u64> {
-    let meta = path.metadata().ok()?;
-    Some(meta.len())
-}
+        assert!(Wait::new_always().condition_met());
+        assert!(!(!Wait::new_always()).condition_met());
+        assert!(!Wait::new_never().condition_met());
+        assert!((!Wait::new_never()).condition_met());
+        assert_eq!((!Wait::new_never()).to_string(), "NOT never()");
+    }
 
-/// Parses a simple human-readable duration, returning a `Duration`
-///
-/// "3h10m" -> 11400 seconds
-pub fn parse_duration(duration: &str) -> Option<Duration> {
-    let mut total_delay = 0;
+    #[test]
+    fn custom_stateful() {
+        use super::Wait;
+        use std::time::Duration;
 
-    let mut acc = 0;
-    for c in duration.chars() {
-        match c {
-            '0'..='9' => {
-                acc *= 10;
-                acc += c.to_digit(10).unwrap();
-            }
-            'd' => {
-                // days
-                total_delay += acc * 86400;
-                acc = 0;
-            }
-            'h' => {
-                // hours
-                total_delay += acc * 3600;
-                acc = 0;
-            }
-            'm' => {
-                // minutes
-                total_delay += acc * 60;
-                acc = 0;
-            }
-            's' => {
-                // seconds
-                total_delay += acc;
-                acc = 0;
-            }
-            _ => return None,
+        let mut checks = 0;
+        let w = Wait::new_custom_stateful(move || {
+            checks += 1;
+            checks % 3 == 0
+        });
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        assert!(w.condition_met());
+
+        // Clones share the state
+        let clone = w.clone();
+        assert!(!clone.condition_met());
+        assert!(!w.condition_met());
+        assert!(clone.condition_met());
+        assert_eq!(w, clone);
+
+        (!w).wait(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn partial_eq() {
+        use super::Wait;
+        use std::time::Duration;
+
+        assert_eq!(Wait::new_file_exists("a"), Wait::new_file_exists("a"));
+        assert_ne!(Wait::new_file_exists("a"), Wait::new_file_exists("b"));
+        assert_ne!(Wait::new_file_exists("a"), !Wait::new_file_exists("a"));
+        assert_ne!(Wait::new_file_exists("a"), Wait::new_dir_exists("a"));
+        assert_ne!(
+            Wait::new_tcp_connect("localhost:80"),
+            Wait::new_tcp_connect("localhost:80").with_tcp_timeout(Duration::from_secs(1))
+        );
+
+        // Runtime state isn't compared
+        let path = std::env::temp_dir().join(format!("waitforit-eq-{}", std::process::id()));
+        std::fs::write(&path, "data").unwrap();
+        let checked = Wait::new_file_size(&path);
+        checked.condition_met();
+        assert_eq!(checked, Wait::new_file_size(&path));
+        std::fs::remove_file(&path).unwrap();
+
+        fn yes() -> bool {
+            true
+        }
+        fn no() -> bool {
+            false
+        }
+        assert_eq!(Wait::new_custom(yes), Wait::new_custom(yes));
+        assert_ne!(Wait::new_custom(yes), Wait::new_custom(no));
+
+        let closure = Wait::new_custom_closure(|| true);
+        assert_eq!(closure.clone(), closure);
+        assert_ne!(Wait::new_custom_closure(|| true), closure);
+
+        #[cfg(feature = "http")]
+        {
+            use crate::MultiMode;
+
+            let url = "http://localhost/";
+            let latency = Wait::new_http_latency(url, Duration::from_secs(1));
+            assert_eq!(latency, Wait::new_http_latency(url, Duration::from_secs(1)));
+            assert_ne!(latency, Wait::new_http_latency(url, Duration::from_secs(2)));
+            assert_eq!(Wait::new_http_head(url, 200), Wait::new_http_head(url, 200));
+            assert_ne!(Wait::new_http_head(url, 200), Wait::new_http_get(url, 200));
+            let multi = Wait::new_http_multi([url], 200, MultiMode::All);
+            assert_eq!(multi, Wait::new_http_multi([url], 200, MultiMode::All));
+            assert_ne!(multi, Wait::new_http_multi([url], 200, MultiMode::Any));
+        }
+        #[cfg(feature = "chrono")]
+        {
+            let nine = Wait::new_time_of_day(9, 0).unwrap();
+            assert_eq!(nine, Wait::new_time_of_day(9, 0).unwrap());
+            assert_ne!(nine, Wait::new_time_of_day(9, 30).unwrap());
         }
     }
 
-    total_delay += acc;
+    #[test]
+    fn valid_tcp() {
+        assert!(super::validate_host_port("localhost:80"));
+        assert!(!super::validate_host_port("localhost"));
 
-    let d = Duration::from_secs(total_delay as u64);
+        assert!(super::validate_host_port("127.0.0.1:80"));
+        assert!(!super::validate_host_port("127.0.0.1"));
 
-    Some(d)
-}
+        assert!(super::validate_host_port("127.0.0.1:8000"));
+        assert!(super::validate_host_port("127.0.0.1:65534"));
+        assert!(super::validate_host_port("127.0.0.1:65535"));
+        assert!(!super::validate_host_port("127.0.0.1:65536"));
+        assert!(!super::validate_host_port("127.0.0.1:-1"));
+    }
 
-/// Parses an input argument for an HTTP GET into the expected status code and URL to hit.
-///
-/// The URL is validated with the `url` crate, if possible, cleaning potential errors.
-/// If that fails, the URL is used as-is.
-#[cfg(feature = "http")]
-pub fn parse_http_get(urlarg: &str) -> (u16, String) {
-    let urlbytes = urlarg.chars().collect::<Vec<_>>();
+    #[test]
+    fn valid_tcp_ipv6() {
+        assert!(super::validate_host_port("[::1]:8080"));
+        assert!(super::validate_host_port("[2001:db8::1]:443"));
 
-    let (status_code, urlarg) = if urlarg.len() > 4
-        && urlbytes[0..3].iter().all(|c| c.is_numeric())
-        && urlbytes[3] == ','
-    {
-        let code = 100 * (urlbytes[0] as u16 - '0' as u16)
-            + 10 * (urlbytes[1] as u16 - '0' as u16)
-            + (urlbytes[2] as u16 - '0' as u16);
+        assert!(!super::validate_host_port("[::1]"));
+        assert!(!super::validate_host_port("[::1]:"));
+        assert!(!super::validate_host_port("[::1]:65536"));
+        assert!(!super::validate_host_port("[localhost]:80"));
+        assert!(!super::validate_host_port("::1:80"));
+    }
 
-        (code, &urlarg[4..])
-    } else {
-        (200, urlarg)
-    };
+    #[test]
+    fn port_zero() {
+        assert!(!super::validate_host_port("localhost:0"));
+        assert!(!super::validate_host_port("[::1]:0"));
+        assert!(super::validate_host_port("localhost:00080"));
+    }
 
-    if let Some(url) = parse_url(urlarg) {
-        (status_code, url.to_string())
-    } else {
-        (status_code, urlarg.to_string())
+    #[test]
+    fn resolve_host_port() {
+        use super::resolve_host_port;
+        use std::net::SocketAddr;
+
+        assert_eq!(
+            resolve_host_port("127.0.0.1:8080"),
+            Some(SocketAddr::from(([127, 0, 0, 1], 8080)))
+        );
+        assert_eq!(
+            resolve_host_port("[::1]:443"),
+            Some("[::1]:443".parse().unwrap())
+        );
+        assert_eq!(
+            resolve_host_port("localhost:80").map(|addr| addr.port()),
+            Some(80)
+        );
+
+        assert_eq!(resolve_host_port("127.0.0.1:0"), None);
+        assert_eq!(resolve_host_port("127.0.0.1"), None);
+        assert_eq!(resolve_host_port("nonexistent.invalid:80"), None);
     }
-}
 
-/// Tries to parse a URL using the `url` crate.
-#[cfg(feature = "http")]
-fn parse_url(urlarg: &str) -> Option<Url> {
-    let violations = std::cell::RefCell::new(Vec::new());
-    let url = Url::options()
-        .syntax_violation_callback(Some(&|v| {
-            violations.borrow_mut().push(v);
-        }))
-        .parse(urlarg)
-        .ok()?;
+    #[test]
+    fn connects_any() {
+        use super::connects_any;
+        use std::{net::TcpListener, time::Duration};
 
-    Some(url)
-}
+        let live = TcpListener::bind("127.0.0.1:0").unwrap();
+        let dead = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (live_addr, dead_addr) = (live.local_addr().unwrap(), dead.local_addr().unwrap());
+        drop(dead);
 
-/// Checks that the input appears to be a valid `hostname:port` input, where `port`
-/// is a u16.
-pub fn validate_tcp(hostarg: &str) -> bool {
-    // Assume that the last location of ':' is the delimiter for the port
-    let last_colon = hostarg.char_indices().filter(|(_i, c)| c == &':').last();
-    if let Some((i, _c)) = last_colon {
-        // Everything after the colon should be a u16 port number
-        let port = &hostarg[i + 1..];
-        port.parse::<u16>().is_ok()
-    } else {
-        // There's no ':' in the input, so assume this isn't a host to which we can connect
-        false
+        let timeout = Duration::from_millis(200);
+        assert!(connects_any([dead_addr, live_addr], timeout));
+        assert!(connects_any([live_addr, dead_addr], timeout));
+        assert!(!connects_any([dead_addr, dead_addr], timeout));
+        assert!(!connects_any([], timeout));
     }
-}
 
-mod tests {
+    #[cfg(target_os = "linux")]
     #[test]
-    fn valid_tcp() {
-        assert!(super::validate_tcp("localhost:80"));
-        assert!(!super::validate_tcp("localhost"));
+    fn tcp_connect_timeout() {
+        use super::{connects_any, Wait};
+        use std::{
+            net::{TcpListener, TcpStream},
+            time::{Duration, Instant},
+        };
+
+        // Once a listener's backlog is full of connections it hasn't accepted,
+        // Linux drops further SYNs, so connecting hangs until it times out
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut backlog = Vec::new();
+        while let Ok(stream) = TcpStream::connect_timeout(&addr, Duration::from_millis(100)) {
+            backlog.push(stream);
+            assert!(backlog.len() < 10_000, "the backlog never filled");
+        }
+
+        let timeout = Duration::from_millis(300);
+        let start = Instant::now();
+        assert!(!connects_any([addr], timeout));
+        let elapsed = start.elapsed();
+        assert!(elapsed >= timeout && elapsed < Duration::from_secs(1));
+
+        let w = Wait::new_tcp_connect(addr.to_string()).with_tcp_timeout(timeout);
+        let start = Instant::now();
+        assert!((!w).condition_met());
+        let elapsed = start.elapsed();
+        assert!(elapsed >= timeout && elapsed < Duration::from_secs(1));
+    }
 
-        assert!(super::validate_tcp("127.0.0.1:80"));
-        assert!(!super::validate_tcp("127.0.0.1"));
+    #[test]
+    fn tcp_connect_ipv6() {
+        use super::Wait;
+
+        // Not every host has IPv6 loopback configured
+        let Ok(listener) = std::net::TcpListener::bind("[::1]:0") else {
+            return;
+        };
+        let port = listener.local_addr().unwrap().port();
+        assert!(Wait::new_tcp_connect(format!("[::1]:{port}")).condition_met());
+    }
 
-        assert!(super::validate_tcp("127.0.0.1:8000"));
-        assert!(super::validate_tcp("127.0.0.1:65534"));
-        assert!(super::validate_tcp("127.0.0.1:65535"));
-        assert!(!super::validate_tcp("127.0.0.1:65536"));
-        assert!(!super::validate_tcp("127.0.0.1:-1"));
+    #[test]
+    fn sync_cell_update() {
+        use super::SyncCell;
+
+        let cell = SyncCell::new(0u32);
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        cell.update(|n| *n += 1);
+                    }
+                });
+            }
+        });
+        assert_eq!(cell.get(), 8000);
+        assert!(cell.update(|n| std::mem::replace(n, 1) == 8000));
+        assert_eq!(cell.get(), 1);
     }
 }
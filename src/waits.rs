@@ -1,14 +1,322 @@
-use crate::Wait;
-use std::time::{Duration, Instant};
+use crate::{poll, Cost, PollSchedule, SyncCell, Wait};
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
-/// Handles waiting for one or more [Wait]s.
+/// Which [Wait]s satisfied a [Waits] condition, as returned by [Waits::wait_which].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaitOutcome {
+    /// Indices of the satisfied [Wait]s, numbered left-to-right from zero in the
+    /// order they appear in the expression. For an `Or`, this is the first branch
+    /// that was met; for an `And`, it includes every [Wait] of both branches.
+    pub satisfied: Vec<usize>,
+}
+
+/// A snapshot of whether a [Waits] condition and each of its parts are met, as
+/// returned by [Waits::describe]. This mirrors the condition's structure, so it
+/// can be rendered as a tree (as its `Display` does):
+///
+/// ```text
+/// OR: met
+///   exists("ready.txt"): met
+///   tcp("localhost:80"): skipped
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaitsStatus {
+    /// A [Wait]'s `Display` for a leaf, or else the combination: `AND`, `OR`,
+    /// `XOR`, `AT LEAST n OF`, `STABLE FOR n CHECKS`, or `SETTLED FOR 30s`.
+    pub label: String,
+    /// Whether this part is met, or `None` if it wasn't checked because of
+    /// short-circuiting.
+    pub met: Option<bool>,
+    /// The status of each of this part's conditions, in order. Empty for a leaf.
+    pub children: Vec<WaitsStatus>,
+}
+
+impl WaitsStatus {
+    /// Whether this part was checked and is met.
+    pub fn is_met(&self) -> bool {
+        self.met == Some(true)
+    }
+}
+
+impl std::fmt::Display for WaitsStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_tree(
+            f: &mut std::fmt::Formatter<'_>,
+            status: &WaitsStatus,
+            depth: usize,
+        ) -> std::fmt::Result {
+            let met = match status.met {
+                Some(true) => "met",
+                Some(false) => "not met",
+                None => "skipped",
+            };
+            write!(
+                f,
+                "{:indent$}{}: {met}",
+                "",
+                status.label,
+                indent = depth * 2
+            )?;
+            for child in &status.children {
+                f.write_str("\n")?;
+                write_tree(f, child, depth + 1)?;
+            }
+            Ok(())
+        }
+
+        write_tree(f, self, 0)
+    }
+}
+
+/// Handles waiting for one or more [Wait]s. Two `Waits` are equal if they have
+/// the same structure of equal [Wait]s (see [Wait]'s `PartialEq`), ignoring
+/// runtime state such as a `Stable`'s count or when a `SettledFor` was first met.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Waits {
     Single(Wait),
     Or(Box<(Waits, Waits)>),
     And(Box<(Waits, Waits)>),
+    /// Met when exactly one of the two conditions is met. Unlike `Or` and `And`,
+    /// there's no short-circuiting: both sides are always evaluated.
+    Xor(Box<(Waits, Waits)>),
+    /// Met when at least `n` of `conditions` are met. See [Waits::n_of].
+    NOf {
+        n: usize,
+        conditions: Vec<Waits>,
+    },
+    /// Met once `inner` has been met on `consecutive` checks in a row. See
+    /// [Waits::stable]. `count` is how many checks in a row it's been met so far.
+    Stable {
+        inner: Box<Waits>,
+        consecutive: u32,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        count: SyncCell<u32>,
+    },
+    /// Met once `inner` has been met continuously for `duration`. See
+    /// [Waits::settled_for]. `since` is when it was first met of the checks it's
+    /// been met in a row so far.
+    SettledFor {
+        inner: Box<Waits>,
+        duration: Duration,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        since: SyncCell<Option<Instant>>,
+    },
 }
 
 impl Waits {
+    /// Combines `conditions` into a single condition that is met when all of them
+    /// are met. They're folded left-to-right (`((a & b) & c) & ...`), so they're
+    /// checked in order, stopping at the first that isn't met.
+    ///
+    /// ```
+    /// use waitforit::{Wait, Waits};
+    ///
+    /// let files = ["a.json", "b.json", "c.json"].map(Wait::new_file_exists);
+    /// let all_exist = Waits::all(files);
+    /// ```
+    ///
+    /// If `conditions` is empty, the result is [Wait::Always].
+    pub fn all<I>(conditions: I) -> Waits
+    where
+        I: IntoIterator,
+        I::Item: Into<Waits>,
+    {
+        conditions
+            .into_iter()
+            .map(Into::into)
+            .reduce(|acc, w| acc & w)
+            .unwrap_or_else(|| Wait::new_always().into())
+    }
+
+    /// Combines `conditions` into a single condition that is met when any of them
+    /// is met. They're folded left-to-right (`((a | b) | c) | ...`), so they're
+    /// checked in order, stopping at the first that is met.
+    ///
+    /// If `conditions` is empty, the result is [Wait::Never].
+    pub fn any<I>(conditions: I) -> Waits
+    where
+        I: IntoIterator,
+        I::Item: Into<Waits>,
+    {
+        conditions
+            .into_iter()
+            .map(Into::into)
+            .reduce(|acc, w| acc | w)
+            .unwrap_or_else(|| Wait::new_never().into())
+    }
+
+    /// Creates a condition that is met when at least `n` of `conditions` are met,
+    /// such as a quorum of hosts being reachable:
+    ///
+    /// ```
+    /// use waitforit::{Wait, Waits};
+    ///
+    /// let hosts = ["db1:5432", "db2:5432", "db3:5432"].map(Wait::new_tcp_connect);
+    /// let quorum = Waits::n_of(2, hosts);
+    /// ```
+    ///
+    /// Conditions are checked in order, stopping as soon as `n` have been met (or
+    /// too few remain for `n` to be reached). When `n` is zero, this is always met;
+    /// when `n` exceeds the number of conditions, it's never met.
+    pub fn n_of<I>(n: usize, conditions: I) -> Waits
+    where
+        I: IntoIterator,
+        I::Item: Into<Waits>,
+    {
+        Waits::NOf {
+            n,
+            conditions: conditions.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Creates a condition that is met once `inner` has been met on `consecutive`
+    /// checks in a row, such as to ignore a flaky health check that's briefly
+    /// healthy while a service is still starting:
+    ///
+    /// ```
+    /// use waitforit::{Wait, Waits};
+    ///
+    /// let healthy = Waits::stable(Wait::new_tcp_connect("localhost:8080"), 3);
+    /// ```
+    ///
+    /// Each check that `inner` isn't met starts the count over. With a
+    /// `consecutive` of 0, this is always met.
+    ///
+    /// Negating this negates `inner`, so `!Waits::stable(w, 3)` is met once `w`
+    /// has been unmet three checks in a row.
+    pub fn stable<W>(inner: W, consecutive: u32) -> Waits
+    where
+        W: Into<Waits>,
+    {
+        Waits::Stable {
+            inner: Box::new(inner.into()),
+            consecutive,
+            count: SyncCell::new(0),
+        }
+    }
+
+    /// Creates a condition that is met once `inner` has been met continuously for
+    /// `duration`, such as to only consider a service up once it's been healthy
+    /// for 30 seconds:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::{Wait, Waits};
+    ///
+    /// let up = Waits::settled_for(
+    ///     Wait::new_tcp_connect("localhost:8080"),
+    ///     Duration::from_secs(30),
+    /// );
+    /// ```
+    ///
+    /// Unlike [Waits::stable], this doesn't depend on how often it's checked:
+    /// the time is measured from the first check that `inner` was met, so it's
+    /// only met on a later check at least `duration` after. Any check that
+    /// `inner` isn't met starts the clock over. With a `duration` of zero, this
+    /// is met as soon as `inner` is.
+    ///
+    /// Negating this negates `inner`, so `!Waits::settled_for(w, d)` is met once
+    /// `w` has been continuously unmet for `d`.
+    pub fn settled_for<W>(inner: W, duration: Duration) -> Waits
+    where
+        W: Into<Waits>,
+    {
+        Waits::SettledFor {
+            inner: Box::new(inner.into()),
+            duration,
+            since: SyncCell::new(None),
+        }
+    }
+
+    /// Combines this condition with a timeout, as `self | elapsed(timeout)`, so
+    /// that waiting for it gives up once `timeout` has passed. The timeout starts
+    /// now, not when a wait starts. Use [Self::wait_or_timeout] to tell whether
+    /// this condition or the timeout ended the wait:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::{Wait, Waits};
+    ///
+    /// let ready = Waits::from(Wait::new_file_exists("/nonexistent/ready"))
+    ///     .with_timeout(Duration::from_millis(50));
+    /// assert!(!ready.wait_or_timeout(Duration::from_millis(10)));
+    /// ```
+    pub fn with_timeout(self, timeout: Duration) -> Waits {
+        self | Wait::new_elapsed_from_duration(timeout)
+    }
+
+    /// The [Cost] of checking this condition's costliest [Wait].
+    pub fn estimated_cost(&self) -> Cost {
+        match self {
+            Waits::Single(w) => w.estimated_cost(),
+            Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                cc.0.estimated_cost().max(cc.1.estimated_cost())
+            }
+            Waits::NOf { conditions, .. } => conditions
+                .iter()
+                .map(Waits::estimated_cost)
+                .max()
+                .unwrap_or(Cost::Local),
+            Waits::Stable { inner, .. } | Waits::SettledFor { inner, .. } => inner.estimated_cost(),
+        }
+    }
+
+    /// Reorders this condition so that cheaper conditions (by [Self::estimated_cost])
+    /// are checked first, such as a file's existence before a network request. An
+    /// `Or` or `And` that short-circuits on its cheap side then skips the costly
+    /// side entirely.
+    ///
+    /// This doesn't change whether the condition is met, as long as its [Wait]s
+    /// don't have side effects. Stateful conditions, like [Wait::FileSize], may
+    /// be checked more or less often than before, and the indices reported by
+    /// [Self::wait_which] refer to the reordered condition. Conditions of equal
+    /// cost stay in their original order.
+    pub fn optimized(self) -> Waits {
+        let reorder = |cc: Box<(Waits, Waits)>| {
+            let (left, right) = (cc.0.optimized(), cc.1.optimized());
+            if left.estimated_cost() > right.estimated_cost() {
+                Box::new((right, left))
+            } else {
+                Box::new((left, right))
+            }
+        };
+
+        match self {
+            Waits::Single(w) => Waits::Single(w),
+            Waits::Or(cc) => Waits::Or(reorder(cc)),
+            Waits::And(cc) => Waits::And(reorder(cc)),
+            // Both sides are always checked, so order doesn't matter
+            Waits::Xor(cc) => Waits::Xor(Box::new((cc.0.optimized(), cc.1.optimized()))),
+            Waits::NOf { n, conditions } => {
+                let mut conditions: Vec<_> = conditions.into_iter().map(Waits::optimized).collect();
+                conditions.sort_by_key(Waits::estimated_cost);
+                Waits::NOf { n, conditions }
+            }
+            Waits::Stable {
+                inner,
+                consecutive,
+                count,
+            } => Waits::Stable {
+                inner: Box::new(inner.optimized()),
+                consecutive,
+                count,
+            },
+            Waits::SettledFor {
+                inner,
+                duration,
+                since,
+            } => Waits::SettledFor {
+                inner: Box::new(inner.optimized()),
+                duration,
+                since,
+            },
+        }
+    }
+
     /// Checks whether this condition - comprising all constituent [Wait]s - is satisfied.
     ///
     /// This is non-blocking, but depending on the conditions that comprise it, it may
@@ -25,26 +333,789 @@ impl Waits {
     /// let ab = (a.clone() | b.clone()).condition_met();
     /// let ba = (b | a).condition_met();
     /// ```
+    ///
+    /// The right side of an `|` isn't checked when its left side is met, nor the
+    /// right side of an `&` when its left side isn't, and an `NOf` stops once its
+    /// outcome is decided. Since this is decided afresh on every check, while a
+    /// cheap condition on the left of an `&` is unmet, an expensive one on its
+    /// right is never polled at all, however long the wait. With the `log` or
+    /// `tracing` feature, [set_log_skipped](crate::set_log_skipped) logs the
+    /// [Wait]s that are skipped this way.
+    ///
+    /// `Xor` can't short-circuit, so both of its sides are always evaluated. See
+    /// [Self::optimized] to check cheap conditions first automatically.
     pub fn condition_met(&self) -> bool {
+        self.evaluate(0, &mut Vec::new(), &mut None)
+    }
+
+    /// Checks whether this condition is satisfied, like [Self::condition_met], but
+    /// checks each distinct [Wait] at most once. This is useful when the same
+    /// condition appears in several branches, such as an expensive health check
+    /// command shared by a builder:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let api = Wait::new_command("pg_isready", ["-h", "localhost"], 0);
+    /// let db = Wait::new_tcp_connect("localhost:5432");
+    /// let cache = Wait::new_tcp_connect("localhost:6379");
+    ///
+    /// // `api` is run once per check, not twice
+    /// let w = (api.clone() & db) | (api & cache);
+    /// let met = w.condition_met_memoized();
+    /// ```
+    ///
+    /// [Wait]s are the same if they're equal (see [Wait]'s `PartialEq`). Only the
+    /// first occurrence of a duplicated `Wait` is checked, and later ones reuse its
+    /// result even if it would have changed in between. For conditions that record
+    /// state between checks, like [Wait::new_file_size], only the first
+    /// occurrence's state is updated. Otherwise, this short-circuits just like
+    /// [Self::condition_met].
+    pub fn condition_met_memoized(&self) -> bool {
+        self.evaluate(0, &mut Vec::new(), &mut Some(Vec::new()))
+    }
+
+    /// Wait for the completion of this condition, checking every `interval` with
+    /// [Self::condition_met_memoized]. This will block the thread.
+    pub fn wait_memoized(&self, interval: Duration) {
+        poll::wait(
+            poll::instrumented(self, Some(interval), || self.condition_met_memoized()),
+            interval,
+        );
+    }
+
+    /// Checks whether this condition is satisfied, like [Self::condition_met], but
+    /// checks all of its [Wait]s at the same time, each on its own thread.
+    ///
+    /// This trades threads for latency: rather than taking the sum of its slow
+    /// conditions' delays, a check takes only as long as it needs to decide the
+    /// result. An `Or` whose fast side is met returns without waiting for its
+    /// slow side, which is left to finish in the background, as is a leaf that
+    /// panics after the result was decided. Every [Wait] is checked every time,
+    /// which matters for stateful conditions like [Wait::FileSize], but a
+    /// [Waits::stable] or [Waits::settled_for] only records a check once the
+    /// result is decided, and only if [Self::condition_met] would have checked it
+    /// given the results in by then. So in `a | stable(b)`, a met `a` leaves the
+    /// `stable` alone even if `b` finished first.
+    ///
+    /// The threads share the condition, so it must be in an [Arc]. Every [Wait]
+    /// (including custom functions and closures) is [Send] and [Sync], so any
+    /// condition can be checked this way.
+    pub fn condition_met_parallel(self: &Arc<Self>) -> bool {
+        let leaves = self.leaf_count();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for index in 0..leaves {
+            let (condition, sender) = (Arc::clone(self), sender.clone());
+            std::thread::spawn(move || {
+                let met = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    condition
+                        .leaves()
+                        .nth(index)
+                        .is_some_and(Wait::condition_met)
+                }));
+                // Once the result is decided without this one, no one's listening
+                let _ = sender.send((index, met));
+            });
+        }
+
+        let mut results = vec![None; leaves];
+        loop {
+            let now = Instant::now();
+            if let Some(met) = self.decide(0, &results, now) {
+                self.record(0, &results, now);
+                return met;
+            }
+
+            // Every leaf sends its result, and all of them together decide it
+            match receiver.recv().expect("a leaf's result went missing") {
+                (index, Ok(met)) => results[index] = Some(met),
+                (_, Err(panic)) => std::panic::resume_unwind(panic),
+            }
+        }
+    }
+
+    /// Decides this condition from the results of those of its [Wait]s that have
+    /// been checked, where `results[i]` is for the leaf numbered `i` in the order
+    /// of [Self::leaves], and this condition's first leaf is `first_leaf`.
+    /// Returns `None` if the results so far aren't enough to decide it.
+    ///
+    /// Nothing is recorded: a `Stable` or `SettledFor` is decided as it would be
+    /// if its check were recorded `now`, which [Self::record] then does.
+    fn decide(&self, first_leaf: usize, results: &[Option<bool>], now: Instant) -> Option<bool> {
+        match self {
+            Waits::Single(_) => results[first_leaf],
+            Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                let left = cc.0.decide(first_leaf, results, now);
+                let right = cc.1.decide(first_leaf + cc.0.leaf_count(), results, now);
+                match self {
+                    Waits::Or(_) if left == Some(true) || right == Some(true) => Some(true),
+                    Waits::And(_) if left == Some(false) || right == Some(false) => Some(false),
+                    Waits::Or(_) => Some(left? || right?),
+                    Waits::And(_) => Some(left? && right?),
+                    _ => Some(left? ^ right?),
+                }
+            }
+            Waits::NOf { n, conditions } => {
+                let (mut met, mut undecided) = (0, 0);
+                let mut leaf = first_leaf;
+                for c in conditions {
+                    match c.decide(leaf, results, now) {
+                        Some(true) => met += 1,
+                        Some(false) => {}
+                        None => undecided += 1,
+                    }
+                    leaf += c.leaf_count();
+                }
+                if met >= *n {
+                    Some(true)
+                } else if met + undecided < *n {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Waits::Stable {
+                inner,
+                consecutive,
+                count,
+            } => {
+                let in_a_row = match inner.decide(first_leaf, results, now)? {
+                    true => count.get().saturating_add(1),
+                    false => 0,
+                };
+                Some(in_a_row >= *consecutive)
+            }
+            Waits::SettledFor {
+                inner,
+                duration,
+                since,
+            } => {
+                let met = inner.decide(first_leaf, results, now)?;
+                let first = since.get().unwrap_or(now);
+                Some(met && now.saturating_duration_since(first) >= *duration)
+            }
+        }
+    }
+
+    /// Records the checks of the `Stable`s and `SettledFor`s in this decided
+    /// condition (see [Self::decide]) that [Self::condition_met] would have
+    /// made: every side that was needed to decide it, skipping those it
+    /// would have short-circuited past and those that are still undecided.
+    fn record(&self, first_leaf: usize, results: &[Option<bool>], now: Instant) {
+        match self {
+            Waits::Single(_) => {}
+            Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                let right_leaf = first_leaf + cc.0.leaf_count();
+                let left = cc.0.decide(first_leaf, results, now);
+                // The value of the left side that short-circuits the right
+                let short_circuit = match self {
+                    Waits::Or(_) => Some(true),
+                    Waits::And(_) => Some(false),
+                    _ => None,
+                };
+                if left.is_some() {
+                    cc.0.record(first_leaf, results, now);
+                }
+                let needed = short_circuit.is_none() || left != short_circuit;
+                if needed && cc.1.decide(right_leaf, results, now).is_some() {
+                    cc.1.record(right_leaf, results, now);
+                }
+            }
+            Waits::NOf { n, conditions } => {
+                let mut leaf = first_leaf;
+                let mut met = 0;
+                for (i, c) in conditions.iter().enumerate() {
+                    if met >= *n || met + (conditions.len() - i) < *n {
+                        break;
+                    }
+                    if let Some(decided) = c.decide(leaf, results, now) {
+                        c.record(leaf, results, now);
+                        met += usize::from(decided);
+                    }
+                    leaf += c.leaf_count();
+                }
+            }
+            Waits::Stable {
+                inner,
+                consecutive,
+                count,
+            } => {
+                // Decided before recording changes the inner condition's state
+                let met = inner.decide(first_leaf, results, now) == Some(true);
+                inner.record(first_leaf, results, now);
+                stable(met, *consecutive, count);
+            }
+            Waits::SettledFor {
+                inner,
+                duration,
+                since,
+            } => {
+                // Decided before recording changes the inner condition's state
+                let met = inner.decide(first_leaf, results, now) == Some(true);
+                inner.record(first_leaf, results, now);
+                settled_at(met, *duration, since, now);
+            }
+        }
+    }
+
+    /// Wait for the completion of this condition, checking every `interval` with
+    /// [Self::condition_met_parallel]. This will block the thread.
+    pub fn wait_parallel(self: &Arc<Self>, interval: Duration) {
+        poll::wait(
+            poll::instrumented(self, Some(interval), || self.condition_met_parallel()),
+            interval,
+        );
+    }
+
+    /// Checks this condition like [Self::condition_met], pushing the indices of the
+    /// leaves that satisfied it onto `satisfied`. Leaves are numbered left-to-right
+    /// starting at `first_leaf`.
+    ///
+    /// Nothing is left in `satisfied` when this returns false. With a `memo`, each
+    /// [Wait] equal to one that's already been checked reuses its result.
+    fn evaluate<'a>(
+        &'a self,
+        first_leaf: usize,
+        satisfied: &mut Vec<usize>,
+        memo: &mut Option<Vec<(&'a Wait, bool)>>,
+    ) -> bool {
+        match self {
+            Waits::Single(u) => {
+                let met = match memo {
+                    None => u.condition_met(),
+                    Some(memo) => match memo.iter().find(|(w, _)| *w == u) {
+                        Some(&(_, met)) => met,
+                        None => {
+                            let met = u.condition_met();
+                            memo.push((u, met));
+                            met
+                        }
+                    },
+                };
+                if met {
+                    satisfied.push(first_leaf);
+                }
+                met
+            }
+            Waits::Or(cc) => {
+                if cc.0.evaluate(first_leaf, satisfied, memo) {
+                    cc.1.skipped();
+                    return true;
+                }
+                cc.1.evaluate(first_leaf + cc.0.leaf_count(), satisfied, memo)
+            }
+            Waits::And(cc) => {
+                let mark = satisfied.len();
+                let met = if cc.0.evaluate(first_leaf, satisfied, memo) {
+                    cc.1.evaluate(first_leaf + cc.0.leaf_count(), satisfied, memo)
+                } else {
+                    cc.1.skipped();
+                    false
+                };
+                if !met {
+                    satisfied.truncate(mark);
+                }
+                met
+            }
+            Waits::Xor(cc) => {
+                let mut left = Vec::new();
+                let mut right = Vec::new();
+                let left_met = cc.0.evaluate(first_leaf, &mut left, memo);
+                let right_met =
+                    cc.1.evaluate(first_leaf + cc.0.leaf_count(), &mut right, memo);
+
+                // At most one of these is non-empty when the condition is met
+                if left_met ^ right_met {
+                    satisfied.append(&mut left);
+                    satisfied.append(&mut right);
+                }
+                left_met ^ right_met
+            }
+            Waits::NOf { n, conditions } => {
+                let mark = satisfied.len();
+                let mut next_leaf = first_leaf;
+                let mut met = 0;
+                for (i, c) in conditions.iter().enumerate() {
+                    if met >= *n || met + (conditions.len() - i) < *n {
+                        conditions[i..].iter().for_each(Waits::skipped);
+                        break;
+                    }
+
+                    if c.evaluate(next_leaf, satisfied, memo) {
+                        met += 1;
+                    }
+                    next_leaf += c.leaf_count();
+                }
+
+                if met < *n {
+                    satisfied.truncate(mark);
+                }
+                met >= *n
+            }
+            Waits::Stable {
+                inner,
+                consecutive,
+                count,
+            } => {
+                let mark = satisfied.len();
+                let met = stable(
+                    inner.evaluate(first_leaf, satisfied, memo),
+                    *consecutive,
+                    count,
+                );
+                if !met {
+                    satisfied.truncate(mark);
+                }
+                met
+            }
+            Waits::SettledFor {
+                inner,
+                duration,
+                since,
+            } => {
+                let mark = satisfied.len();
+                let met = settled(
+                    inner.evaluate(first_leaf, satisfied, memo),
+                    *duration,
+                    since,
+                );
+                if !met {
+                    satisfied.truncate(mark);
+                }
+                met
+            }
+        }
+    }
+
+    /// Checks this condition once, returning whether it and each of its parts are
+    /// met, such as to report on a status page which conditions are still
+    /// outstanding.
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let w = Wait::new_always() | Wait::new_file_exists("ready.txt");
+    /// let status = w.describe();
+    /// assert!(status.is_met());
+    /// assert_eq!(status.children[1].met, None);
+    /// ```
+    ///
+    /// This short-circuits like [Self::condition_met], so parts that don't
+    /// affect the result aren't checked, and have a `met` of `None`. See
+    /// [Self::describe_all] to check them anyway. As with any check, a
+    /// [Waits::stable] or [Waits::settled_for] records its inner condition's
+    /// result.
+    pub fn describe(&self) -> WaitsStatus {
+        self.status(true)
+    }
+
+    /// Checks this condition once like [Self::describe], but without
+    /// short-circuiting, so every part is checked and has a `met`.
+    pub fn describe_all(&self) -> WaitsStatus {
+        self.status(false)
+    }
+
+    fn status(&self, short_circuit: bool) -> WaitsStatus {
+        // The status of `w`, unless short-circuiting skips it because `decided`
+        // is the result of the status before it, `prev`
+        let next = |w: &Waits, prev: &WaitsStatus, decided: bool| {
+            if short_circuit && prev.met == Some(decided) {
+                w.skipped();
+                w.skipped_status()
+            } else {
+                w.status(short_circuit)
+            }
+        };
+
+        let (met, children) = match self {
+            Waits::Single(w) => (w.condition_met(), Vec::new()),
+            Waits::Or(cc) => {
+                let left = cc.0.status(short_circuit);
+                let right = next(&cc.1, &left, true);
+                (left.is_met() || right.is_met(), vec![left, right])
+            }
+            Waits::And(cc) => {
+                let left = cc.0.status(short_circuit);
+                let right = next(&cc.1, &left, false);
+                (left.is_met() && right.is_met(), vec![left, right])
+            }
+            Waits::Xor(cc) => {
+                let (left, right) = (cc.0.status(short_circuit), cc.1.status(short_circuit));
+                (left.is_met() ^ right.is_met(), vec![left, right])
+            }
+            Waits::NOf { n, conditions } => {
+                let mut met = 0;
+                let mut children = Vec::with_capacity(conditions.len());
+                for (i, c) in conditions.iter().enumerate() {
+                    if short_circuit && (met >= *n || met + (conditions.len() - i) < *n) {
+                        c.skipped();
+                        children.push(c.skipped_status());
+                        continue;
+                    }
+                    let status = c.status(short_circuit);
+                    if status.is_met() {
+                        met += 1;
+                    }
+                    children.push(status);
+                }
+                (met >= *n, children)
+            }
+            Waits::Stable {
+                inner,
+                consecutive,
+                count,
+            } => {
+                let inner = inner.status(short_circuit);
+                (stable(inner.is_met(), *consecutive, count), vec![inner])
+            }
+            Waits::SettledFor {
+                inner,
+                duration,
+                since,
+            } => {
+                let inner = inner.status(short_circuit);
+                (settled(inner.is_met(), *duration, since), vec![inner])
+            }
+        };
+
+        WaitsStatus {
+            label: self.label(),
+            met: Some(met),
+            children,
+        }
+    }
+
+    /// The status of this condition when it isn't checked at all.
+    fn skipped_status(&self) -> WaitsStatus {
+        let children = match self {
+            Waits::Single(_) => Vec::new(),
+            Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                vec![cc.0.skipped_status(), cc.1.skipped_status()]
+            }
+            Waits::NOf { conditions, .. } => conditions.iter().map(Waits::skipped_status).collect(),
+            Waits::Stable { inner, .. } | Waits::SettledFor { inner, .. } => {
+                vec![inner.skipped_status()]
+            }
+        };
+
+        WaitsStatus {
+            label: self.label(),
+            met: None,
+            children,
+        }
+    }
+
+    /// This condition's [WaitsStatus::label].
+    fn label(&self) -> String {
+        match self {
+            Waits::Single(w) => w.to_string(),
+            Waits::Or(_) => "OR".to_string(),
+            Waits::And(_) => "AND".to_string(),
+            Waits::Xor(_) => "XOR".to_string(),
+            Waits::NOf { n, .. } => format!("AT LEAST {n} OF"),
+            Waits::Stable { consecutive, .. } => format!("STABLE FOR {consecutive} CHECKS"),
+            Waits::SettledFor { duration, .. } => {
+                format!("SETTLED FOR {}", crate::wait::format_duration(*duration))
+            }
+        }
+    }
+
+    /// Notes that this condition wasn't checked because of short-circuiting,
+    /// logging each of its [Wait]s as skipped if
+    /// [set_log_skipped](crate::set_log_skipped) is enabled.
+    fn skipped(&self) {
+        #[cfg(any(feature = "log", feature = "tracing"))]
+        if poll::log_skipped() {
+            for leaf in self.leaves() {
+                #[cfg(feature = "log")]
+                log::debug!("{leaf}: skipped");
+                #[cfg(feature = "tracing")]
+                tracing::trace!(condition = %leaf, skipped = true);
+            }
+        }
+    }
+
+    /// The number of [Wait]s that comprise this condition.
+    fn leaf_count(&self) -> usize {
+        self.leaves().count()
+    }
+
+    /// Forgets anything this condition has recorded from previous checks, so
+    /// that it can be reused for a fresh wait. This resets each of its [Wait]s
+    /// (see [Wait::reset]), as well as the progress of any [Waits::stable] or
+    /// [Waits::settled_for].
+    pub fn reset(&self) {
         match self {
-            Waits::Single(u) => u.condition_met(),
-            Waits::Or(cc) => cc.0.condition_met() || cc.1.condition_met(),
-            Waits::And(cc) => cc.0.condition_met() && cc.1.condition_met(),
+            Waits::Single(w) => w.reset(),
+            Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                cc.0.reset();
+                cc.1.reset();
+            }
+            Waits::NOf { conditions, .. } => conditions.iter().for_each(Waits::reset),
+            Waits::Stable { inner, count, .. } => {
+                inner.reset();
+                count.set(0);
+            }
+            Waits::SettledFor { inner, since, .. } => {
+                inner.reset();
+                since.set(None);
+            }
         }
     }
 
+    /// Iterates over the [Wait]s that comprise this condition, left-to-right in
+    /// the order they appear in the expression; the index of each is the one
+    /// used by [WaitOutcome::satisfied]. This can be used to report the status
+    /// of each condition:
+    ///
+    /// ```
+    /// use waitforit::Wait;
+    ///
+    /// let w = Wait::new_file_exists("a.txt") & Wait::new_file_exists("b.txt");
+    /// for leaf in w.leaves() {
+    ///     println!("{leaf}: {}", leaf.condition_met());
+    /// }
+    /// ```
+    pub fn leaves(&self) -> impl Iterator<Item = &Wait> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            while let Some(w) = stack.pop() {
+                match w {
+                    Waits::Single(w) => return Some(w),
+                    Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                        stack.push(&cc.1);
+                        stack.push(&cc.0);
+                    }
+                    Waits::NOf { conditions, .. } => stack.extend(conditions.iter().rev()),
+                    Waits::Stable { inner, .. } | Waits::SettledFor { inner, .. } => {
+                        stack.push(inner)
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Like [Waits::leaves], but allows each [Wait] to be modified in place.
+    pub fn leaves_mut(&mut self) -> impl Iterator<Item = &mut Wait> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            while let Some(w) = stack.pop() {
+                match w {
+                    Waits::Single(w) => return Some(w),
+                    Waits::Or(cc) | Waits::And(cc) | Waits::Xor(cc) => {
+                        let (first, second) = &mut **cc;
+                        stack.push(second);
+                        stack.push(first);
+                    }
+                    Waits::NOf { conditions, .. } => stack.extend(conditions.iter_mut().rev()),
+                    Waits::Stable { inner, .. } | Waits::SettledFor { inner, .. } => {
+                        stack.push(inner)
+                    }
+                }
+            }
+            None
+        })
+    }
+
     /// Wait for the completion of this condition. This will block the thread.
     pub fn wait(&self, interval: Duration) {
-        loop {
-            let start = Instant::now();
-            if self.condition_met() {
-                return;
-            }
+        poll::wait(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        );
+    }
+
+    /// Wait for the completion of this condition, checking at intervals decided by
+    /// `schedule` (eg, [ExponentialBackoff](crate::ExponentialBackoff)). This
+    /// will block the thread.
+    pub fn wait_with_schedule(&self, schedule: &dyn PollSchedule) {
+        poll::wait_with_schedule(
+            poll::instrumented(self, None, || self.condition_met()),
+            schedule,
+        );
+    }
+
+    /// Wait for the completion of this condition, checking every `interval` give or
+    /// take a random amount of up to `jitter`. This spreads out checks from many
+    /// processes that start at the same time, though it works best with a
+    /// backoff (see [Jittered](crate::Jittered)). This will block the thread.
+    #[cfg(feature = "rand")]
+    pub fn wait_jittered(&self, interval: Duration, jitter: Duration) {
+        let schedule = crate::Jittered {
+            schedule: crate::FixedInterval(interval),
+            jitter,
+        };
+        self.wait_with_schedule(&schedule);
+    }
+
+    /// Wait for the completion of this condition, checking it at most
+    /// `max_attempts` times, every `interval`. This will block the thread.
+    ///
+    /// Returns `true` if the condition was met and `false` if every attempt was
+    /// used up first. This is useful when each check is costly (eg, running a
+    /// command) and the number of checks matters more than the time taken. With
+    /// zero `max_attempts`, this checks nothing and returns `false`.
+    pub fn wait_max_attempts(&self, interval: Duration, max_attempts: u32) -> bool {
+        poll::wait_max_attempts(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+            max_attempts,
+        )
+    }
+
+    /// Wait for the completion of this condition or for `cancel` to be set (eg,
+    /// by another thread during shutdown), checking every `interval`. This will
+    /// block the thread.
+    ///
+    /// Returns `true` if the condition was met and `false` if the wait was
+    /// cancelled. See [Wait::wait_cancellable].
+    pub fn wait_cancellable(&self, interval: Duration, cancel: &Arc<AtomicBool>) -> bool {
+        poll::wait_cancellable(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+            cancel,
+        )
+    }
+
+    /// Wait for the completion of this condition or for the user to press Ctrl-C,
+    /// checking every `interval`. This will block the thread. See
+    /// [Wait::wait_interruptible] for how Ctrl-C is handled.
+    #[cfg(feature = "ctrlc")]
+    pub fn wait_interruptible(&self, interval: Duration) -> crate::WaitResult {
+        poll::wait_interruptible(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        )
+    }
+
+    /// Wait for the completion of this condition, checking every `interval` and
+    /// calling `on_poll` after each check with the attempt number (counting from
+    /// zero) and whether the condition was met. This will block the thread.
+    ///
+    /// The check that meets the condition is reported too, before this returns.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let w = Wait::new_elapsed_from_duration(Duration::from_millis(20))
+    ///     | Wait::new_file_exists("done.flag");
+    /// w.wait_with_callback(Duration::from_millis(10), |attempt, met| {
+    ///     if !met {
+    ///         println!("attempt {attempt}: still waiting");
+    ///     }
+    /// });
+    /// ```
+    pub fn wait_with_callback<F>(&self, interval: Duration, mut on_poll: F)
+    where
+        F: FnMut(u32, bool),
+    {
+        let mut attempt = 0;
+        let condition = || {
+            let met = self.condition_met();
+            on_poll(attempt, met);
+            attempt += 1;
+            met
+        };
+        poll::wait(
+            poll::instrumented(self, Some(interval), condition),
+            interval,
+        );
+    }
+
+    /// Wait for the completion of this condition on a new thread, checking every
+    /// `interval`, without blocking this one. The returned handle's thread
+    /// finishes, with `true`, once the condition is met; use
+    /// [JoinHandle::is_finished] to check on it without blocking, or
+    /// [JoinHandle::join] to block until then.
+    ///
+    /// Every `Waits` is [Send], so this is always possible. The runtime state of
+    /// its conditions, such as [Wait::FileSize], moves to the new thread along with `self`.
+    pub fn spawn(self, interval: Duration) -> JoinHandle<bool> {
+        std::thread::spawn(move || {
+            self.wait(interval);
+            true
+        })
+    }
+
+    /// Wait for the completion of this condition, returning how long it took,
+    /// from the start of the first check to the end of the one that was
+    /// satisfied. This will block the thread.
+    pub fn wait_elapsed(&self, interval: Duration) -> Duration {
+        poll::wait(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        )
+    }
+
+    /// Wait for the completion of this condition, returning how many checks it
+    /// took. This will block the thread. See [Wait::wait_counted].
+    pub fn wait_counted(&self, interval: Duration) -> u32 {
+        poll::wait_counted(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+        )
+    }
+
+    /// Wait for the completion of this condition, reporting which of its [Wait]s
+    /// satisfied it. This will block the thread.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use waitforit::Wait;
+    ///
+    /// let started = Wait::new_custom(|| false);
+    /// let timed_out = Wait::new_elapsed_from_duration(Duration::ZERO);
+    ///
+    /// let outcome = (started | timed_out).wait_which(Duration::from_millis(10));
+    /// assert_eq!(outcome.satisfied, vec![1]);
+    /// ```
+    pub fn wait_which(&self, interval: Duration) -> WaitOutcome {
+        let mut satisfied = Vec::new();
+        let condition = || {
+            satisfied.clear();
+            self.evaluate(0, &mut satisfied, &mut None)
+        };
+        poll::wait(
+            poll::instrumented(self, Some(interval), condition),
+            interval,
+        );
 
-            let loop_time = start.elapsed();
-            if interval > loop_time {
-                std::thread::sleep(interval - loop_time);
+        WaitOutcome { satisfied }
+    }
+
+    /// Wait for the completion of this condition, giving up once `timeout` has
+    /// elapsed. This will block the thread.
+    ///
+    /// Returns `true` if the condition was met and `false` if the timeout elapsed
+    /// first. The condition is checked at least once, so a zero `timeout` does a
+    /// single check and returns immediately.
+    pub fn wait_timeout(&self, interval: Duration, timeout: Duration) -> bool {
+        poll::wait_timeout(
+            poll::instrumented(self, Some(interval), || self.condition_met()),
+            interval,
+            timeout,
+        )
+    }
+
+    /// Wait for the completion of a condition built by [Self::with_timeout],
+    /// returning `true` if the original condition was met, or `false` if the
+    /// timeout elapsed first. This will block the thread.
+    ///
+    /// The original condition is checked first, so if both are met on the same
+    /// check, it was met. The timeout is recognized as the right side of an `|`
+    /// that's an unnegated [Wait::Elapsed], so rearranging the condition (eg,
+    /// with [Self::optimized]) loses track of it. For any other condition, this
+    /// is just [Self::wait], returning `true`.
+    pub fn wait_or_timeout(&self, interval: Duration) -> bool {
+        let outcome = self.wait_which(interval);
+        match self {
+            Waits::Or(cc) if matches!(cc.1, Waits::Single(Wait::Elapsed { not: false, .. })) => {
+                // The index of the timeout's leaf
+                !outcome.satisfied.contains(&cc.0.leaf_count())
             }
+            _ => true,
         }
     }
 }
@@ -119,6 +1190,38 @@ impl std::ops::BitOr for Waits {
     }
 }
 
+impl std::ops::BitXor for Wait {
+    type Output = Waits;
+
+    fn bitxor(self, other: Wait) -> Self::Output {
+        Waits::Xor(Box::new((self.into(), other.into())))
+    }
+}
+
+impl std::ops::BitXor<Waits> for Wait {
+    type Output = Waits;
+
+    fn bitxor(self, other: Waits) -> Self::Output {
+        Waits::Xor(Box::new((self.into(), other)))
+    }
+}
+
+impl std::ops::BitXor<Wait> for Waits {
+    type Output = Self;
+
+    fn bitxor(self, other: Wait) -> Self {
+        Waits::Xor(Box::new((self, other.into())))
+    }
+}
+
+impl std::ops::BitXor for Waits {
+    type Output = Self;
+
+    fn bitxor(self, other: Waits) -> Self {
+        Waits::Xor(Box::new((self, other)))
+    }
+}
+
 impl std::ops::Not for Waits {
     type Output = Self;
 
@@ -135,6 +1238,648 @@ impl std::ops::Not for Waits {
                 let (w0, w1) = (!ww.0, !ww.1);
                 Waits::Or(Box::new((w0, w1)))
             }
+            Waits::Xor(ww) => {
+                // !(a ^ b) == (!a ^ b)
+                let (w0, w1) = (!ww.0, ww.1);
+                Waits::Xor(Box::new((w0, w1)))
+            }
+            Waits::NOf { n, conditions } => {
+                // Fewer than n of m are met iff at least m-n+1 are not met
+                let n = (conditions.len() + 1).saturating_sub(n);
+                let conditions = conditions.into_iter().map(|c| !c).collect();
+                Waits::NOf { n, conditions }
+            }
+            // Debounce the negated condition, starting the count over
+            Waits::Stable {
+                inner, consecutive, ..
+            } => Waits::stable(!*inner, consecutive),
+            Waits::SettledFor {
+                inner, duration, ..
+            } => Waits::settled_for(!*inner, duration),
+        }
+    }
+}
+
+impl PartialEq for Waits {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Waits::Single(w), Waits::Single(w2)) => w == w2,
+            (Waits::Or(cc), Waits::Or(cc2))
+            | (Waits::And(cc), Waits::And(cc2))
+            | (Waits::Xor(cc), Waits::Xor(cc2)) => cc == cc2,
+            (
+                Waits::NOf { n, conditions },
+                Waits::NOf {
+                    n: n2,
+                    conditions: conditions2,
+                },
+            ) => n == n2 && conditions == conditions2,
+            (
+                Waits::Stable {
+                    inner, consecutive, ..
+                },
+                Waits::Stable {
+                    inner: inner2,
+                    consecutive: consecutive2,
+                    ..
+                },
+            ) => inner == inner2 && consecutive == consecutive2,
+            (
+                Waits::SettledFor {
+                    inner, duration, ..
+                },
+                Waits::SettledFor {
+                    inner: inner2,
+                    duration: duration2,
+                    ..
+                },
+            ) => inner == inner2 && duration == duration2,
+            _ => false,
+        }
+    }
+}
+
+/// Records a check of a [Waits::Stable]'s inner condition, which was `met`,
+/// returning whether it's now been met `consecutive` times in a row.
+pub(crate) fn stable(met: bool, consecutive: u32, count: &SyncCell<u32>) -> bool {
+    count.update(|in_a_row| {
+        *in_a_row = if met { in_a_row.saturating_add(1) } else { 0 };
+        *in_a_row >= consecutive
+    })
+}
+
+/// Records a check of a [Waits::SettledFor]'s inner condition, which was `met`,
+/// returning whether it's now been met continuously for `duration`.
+pub(crate) fn settled(met: bool, duration: Duration, since: &SyncCell<Option<Instant>>) -> bool {
+    settled_at(met, duration, since, Instant::now())
+}
+
+/// Like [settled], for a check made at `now`.
+fn settled_at(
+    met: bool,
+    duration: Duration,
+    since: &SyncCell<Option<Instant>>,
+    now: Instant,
+) -> bool {
+    if !met {
+        since.set(None);
+        return false;
+    }
+
+    let first = since.update(|since| *since.get_or_insert(now));
+    now.duration_since(first) >= duration
+}
+
+/// Renders this condition as an expression of its [Wait]s (see their `Display`)
+/// joined by `AND`, `OR`, and `XOR`, eg, `(exists("foo.txt") AND NOT
+/// elapsed(10s)) OR tcp("host:80")`. Every nested combination is parenthesized,
+/// so the structure is unambiguous. An `NOf` is rendered as `AT LEAST n OF (a,
+/// b, ...)`, a `Stable` as `STABLE FOR n CHECKS (inner)`, and a `SettledFor` as
+/// `SETTLED FOR 30s (inner)`.
+impl std::fmt::Display for Waits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Writes `w`, parenthesized if it's a combination.
+        fn nested(f: &mut std::fmt::Formatter<'_>, w: &Waits) -> std::fmt::Result {
+            match w {
+                Waits::Single(w) => write!(f, "{w}"),
+                _ => write!(f, "({w})"),
+            }
+        }
+
+        let (cc, op) = match self {
+            Waits::Single(w) => return write!(f, "{w}"),
+            Waits::Or(cc) => (cc, "OR"),
+            Waits::And(cc) => (cc, "AND"),
+            Waits::Xor(cc) => (cc, "XOR"),
+            Waits::NOf { n, conditions } => {
+                write!(f, "AT LEAST {n} OF (")?;
+                for (i, c) in conditions.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    nested(f, c)?;
+                }
+                return f.write_str(")");
+            }
+            Waits::Stable {
+                inner, consecutive, ..
+            } => {
+                return write!(f, "STABLE FOR {consecutive} CHECKS ({inner})");
+            }
+            Waits::SettledFor {
+                inner, duration, ..
+            } => {
+                let duration = crate::wait::format_duration(*duration);
+                return write!(f, "SETTLED FOR {duration} ({inner})");
+            }
+        };
+
+        nested(f, &cc.0)?;
+        write!(f, " {op} ")?;
+        nested(f, &cc.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Wait, Waits};
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    fn constant(value: bool) -> Wait {
+        Wait::new_custom_closure(move || value)
+    }
+
+    #[test]
+    fn negation_de_morgan() {
+        for i in 0..8 {
+            let (a, b, c) = (i & 1 != 0, i & 2 != 0, i & 4 != 0);
+
+            let expr = (constant(a) & constant(b)) | constant(c);
+            let expected = !((a && b) || c);
+            assert_eq!((!expr).condition_met(), expected);
+
+            // !((a & b) | c) == (!a | !b) & !c
+            let expanded = (!constant(a) | !constant(b)) & !constant(c);
+            assert_eq!(expanded.condition_met(), expected);
+        }
+    }
+
+    #[test]
+    fn double_negation() {
+        for value in [false, true] {
+            let w = constant(value) & (constant(true) | constant(value));
+            assert_eq!((!!w).condition_met(), value);
+        }
+    }
+
+    #[test]
+    fn xor() {
+        for i in 0..4 {
+            let (a, b) = (i & 1 != 0, i & 2 != 0);
+            let w = constant(a) ^ constant(b);
+            assert_eq!(w.condition_met(), a ^ b);
+
+            let w = constant(a) ^ constant(b);
+            assert_eq!((!w).condition_met(), !(a ^ b));
+        }
+    }
+
+    #[test]
+    fn all_any() {
+        assert!(Waits::all(Vec::<Waits>::new()).condition_met());
+        assert!(!Waits::any(Vec::<Waits>::new()).condition_met());
+        assert!(Waits::all(Vec::<Waits>::new()) == Wait::new_always().into());
+        assert!(Waits::any(Vec::<Waits>::new()) == Wait::new_never().into());
+
+        assert!(Waits::all([constant(true), constant(true)]).condition_met());
+        assert!(!Waits::all([constant(true), constant(false)]).condition_met());
+        assert!(Waits::any([constant(false), constant(true)]).condition_met());
+        assert!(!Waits::any([constant(false), constant(false)]).condition_met());
+
+        let w = Waits::any([constant(false), constant(true), constant(true)]);
+        assert_eq!(w.wait_which(Duration::from_millis(1)).satisfied, vec![1]);
+    }
+
+    #[test]
+    fn n_of() {
+        let three = || [constant(true), constant(false), constant(true)];
+        assert!(Waits::n_of(0, three()).condition_met());
+        assert!(Waits::n_of(2, three()).condition_met());
+        assert!(!Waits::n_of(3, three()).condition_met());
+        assert!(!Waits::n_of(4, three()).condition_met());
+        assert!(Waits::n_of(0, Vec::<Waits>::new()).condition_met());
+
+        for n in 0..5 {
+            let expected = !Waits::n_of(n, three()).condition_met();
+            assert_eq!((!Waits::n_of(n, three())).condition_met(), expected);
+        }
+
+        let w = Waits::n_of(2, three());
+        assert_eq!(w.wait_which(Duration::from_millis(1)).satisfied, vec![0, 2]);
+    }
+
+    #[test]
+    fn optimized() {
+        use crate::Cost;
+
+        let slow = || Wait::new_tcp_connect("localhost:1");
+        let fast = || Wait::new_file_exists("Cargo.toml");
+        let run = || Wait::new_command("true", Vec::<String>::new(), 0);
+
+        let w = (slow() | fast()) & run();
+        assert_eq!(w.estimated_cost(), Cost::Network);
+        let Waits::And(cc) = w.optimized() else {
+            panic!("expected And")
+        };
+        assert_eq!(cc.0.estimated_cost(), Cost::Subprocess);
+        let Waits::Or(or) = &cc.1 else {
+            panic!("expected Or")
+        };
+        assert!(matches!(or.0, Waits::Single(Wait::Exists { .. })));
+
+        let Waits::NOf { conditions, .. } = Waits::n_of(1, [slow(), run(), fast()]).optimized()
+        else {
+            panic!("expected NOf")
+        };
+        let costs: Vec<_> = conditions.iter().map(Waits::estimated_cost).collect();
+        assert_eq!(costs, [Cost::Local, Cost::Subprocess, Cost::Network]);
+    }
+
+    #[test]
+    fn parallel() {
+        use std::time::Instant;
+
+        fn slow(value: bool, millis: u64) -> Wait {
+            Wait::new_custom_closure(move || {
+                std::thread::sleep(Duration::from_millis(millis));
+                value
+            })
+        }
+
+        // Each takes about as long as its slowest leaf
+        for (w, expected) in [
+            (slow(true, 100) & slow(true, 100), true),
+            (slow(false, 100) | slow(true, 100), true),
+            (slow(true, 100) ^ slow(true, 100), false),
+            (
+                Waits::n_of(2, [slow(true, 100), slow(false, 100), slow(true, 100)]),
+                true,
+            ),
+        ] {
+            let start = Instant::now();
+            assert_eq!(Arc::new(w).condition_met_parallel(), expected);
+            assert!(start.elapsed() < Duration::from_millis(190));
+        }
+
+        // Each is decided without waiting for its slow leaf
+        for (w, expected) in [
+            (slow(true, 1000) | constant(true), true),
+            (constant(false) & slow(true, 1000), false),
+            (
+                Waits::n_of(2, [constant(true), slow(false, 1000), constant(true)]),
+                true,
+            ),
+            (
+                Waits::n_of(2, [constant(false), slow(true, 1000), constant(false)]),
+                false,
+            ),
+            (Waits::stable(constant(true) | slow(true, 1000), 1), true),
+        ] {
+            let start = Instant::now();
+            assert_eq!(Arc::new(w).condition_met_parallel(), expected);
+            assert!(start.elapsed() < Duration::from_millis(500));
+        }
+
+        // Stable only counts the checks whose result was needed
+        let w = Arc::new(constant(true) | Waits::stable(slow(true, 100), 2));
+        assert!(w.condition_met_parallel());
+        let Waits::Or(cc) = &*w else {
+            panic!("expected Or")
+        };
+        let Waits::Stable { count, .. } = &cc.1 else {
+            panic!("expected Stable")
+        };
+        assert_eq!(count.get(), 0);
+
+        // The same holds when the stable's inner condition finishes first, as
+        // long as it doesn't decide the result alone
+        fn stable_count(w: &Waits) -> u32 {
+            let Waits::Or(cc) = w else {
+                panic!("expected Or")
+            };
+            let Waits::Stable { count, .. } = &cc.1 else {
+                panic!("expected Stable")
+            };
+            count.get()
+        }
+        let w = Arc::new(slow(true, 100) | Waits::stable(constant(true), 2));
+        assert!(w.condition_met_parallel());
+        assert_eq!(stable_count(&w), 0);
+        let w = Arc::new(slow(false, 100) | Waits::stable(constant(true), 2));
+        assert!(!w.condition_met_parallel());
+        assert_eq!(stable_count(&w), 1);
+        let w = Arc::new(slow(true, 100) | Waits::stable(constant(true), 1));
+        assert!(w.condition_met_parallel());
+        assert_eq!(stable_count(&w), 1);
+
+        // Nested state is recorded as a sequential check would record it
+        let parallel = Arc::new(Waits::stable(Waits::stable(constant(true), 2), 2));
+        let sequential = Waits::stable(Waits::stable(constant(true), 2), 2);
+        for _ in 0..4 {
+            assert_eq!(
+                parallel.condition_met_parallel(),
+                sequential.condition_met()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "leaf panicked")]
+    fn parallel_panic() {
+        let w = constant(false) | Wait::new_custom(|| panic!("leaf panicked"));
+        Arc::new(w).condition_met_parallel();
+    }
+
+    #[test]
+    fn spawn() {
+        let w = Wait::new_elapsed_from_duration(Duration::from_millis(20)) & constant(true);
+        let handle = w.spawn(Duration::from_millis(1));
+        assert!(handle.join().unwrap());
+
+        let ready = Arc::new(AtomicBool::new(false));
+        let r = ready.clone();
+        let w = constant(false) | Wait::new_custom_closure(move || r.load(Ordering::SeqCst));
+        let handle = w.spawn(Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!handle.is_finished());
+
+        ready.store(true, Ordering::SeqCst);
+        assert!(handle.join().unwrap());
+    }
+
+    #[test]
+    fn outcome_or() {
+        let w = Wait::new_custom(|| false) | Wait::new_custom(|| true) | Wait::new_custom(|| true);
+        let outcome = w.wait_which(Duration::from_millis(1));
+        assert_eq!(outcome.satisfied, vec![1]);
+    }
+
+    #[test]
+    fn outcome_and() {
+        let a = Wait::new_custom(|| true) & Wait::new_custom(|| true);
+        let b = Wait::new_custom(|| true) & Wait::new_custom(|| false);
+        let outcome = (b | a).wait_which(Duration::from_millis(1));
+        assert_eq!(outcome.satisfied, vec![2, 3]);
+    }
+
+    #[test]
+    fn outcome_xor() {
+        let w = (constant(true) & constant(false)) ^ constant(true);
+        let outcome = w.wait_which(Duration::from_millis(1));
+        assert_eq!(outcome.satisfied, vec![2]);
+    }
+
+    #[test]
+    fn partial_eq() {
+        let exists = |path| Wait::new_file_exists(path);
+        assert!(exists("a") | exists("b") == exists("a") | exists("b"));
+        assert!(exists("a") | exists("b") != exists("b") | exists("a"));
+        assert!(exists("a") | exists("b") != exists("a") & exists("b"));
+        assert!(!(exists("a") | exists("b")) == !exists("a") & !exists("b"));
+    }
+
+    #[test]
+    fn display() {
+        let w = (Wait::new_file_exists("foo.txt")
+            & !Wait::new_elapsed_from_duration(Duration::from_secs(10)))
+            | Wait::new_tcp_connect("host:80");
+        assert_eq!(
+            w.to_string(),
+            r#"(exists("foo.txt") AND NOT elapsed(10s)) OR tcp("host:80")"#
+        );
+
+        // Negation is pushed down to the leaves
+        let w = !(Wait::new_file_exists("a")
+            | (Wait::new_file_exists("b") ^ Wait::new_file_exists("c")));
+        assert_eq!(
+            w.to_string(),
+            r#"NOT exists("a") AND (NOT exists("b") XOR exists("c"))"#
+        );
+
+        let w = Waits::n_of(
+            2,
+            [
+                Wait::new_dns_resolves("a").into(),
+                Wait::new_dns_resolves("b") & Wait::new_dns_resolves("c"),
+                Wait::new_dns_resolves("d").into(),
+            ],
+        );
+        assert_eq!(
+            w.to_string(),
+            r#"AT LEAST 2 OF (dns("a"), (dns("b") AND dns("c")), dns("d"))"#
+        );
+    }
+
+    #[test]
+    fn stable() {
+        let checks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let c = checks.clone();
+        // Met on checks 0, 2, 3, 4, ...
+        let flaky = Wait::new_custom_closure(move || c.fetch_add(1, Ordering::SeqCst) != 1);
+        let w = Waits::stable(flaky, 3);
+
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        assert!(w.condition_met());
+        assert!(w.condition_met());
+        assert_eq!(w.to_string(), "STABLE FOR 3 CHECKS (custom())");
+
+        w.reset();
+        assert!(!w.condition_met());
+        assert!(!w.condition_met());
+        assert!(w.condition_met());
+
+        assert!(Waits::stable(constant(false), 0).condition_met());
+        let w = !Waits::stable(constant(false), 2);
+        assert!(!w.condition_met());
+        assert!(w.condition_met());
+        assert_eq!(w.estimated_cost(), crate::Cost::Local);
+
+        let w = Waits::stable(constant(true) & constant(true), 2);
+        assert_eq!(w.wait_which(Duration::from_millis(1)).satisfied, vec![0, 1]);
+    }
+
+    #[test]
+    fn settled_for() {
+        let healthy = Arc::new(AtomicBool::new(true));
+        let h = healthy.clone();
+        let w = Waits::settled_for(
+            Wait::new_custom_closure(move || h.load(Ordering::SeqCst)),
+            Duration::from_millis(50),
+        );
+        assert_eq!(w.to_string(), "SETTLED FOR 50ms (custom())");
+
+        assert!(!w.condition_met());
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(w.condition_met());
+
+        // Flapping starts the clock over
+        healthy.store(false, Ordering::SeqCst);
+        assert!(!w.condition_met());
+        healthy.store(true, Ordering::SeqCst);
+        assert!(!w.condition_met());
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(w.condition_met());
+
+        assert!(Waits::settled_for(constant(true), Duration::ZERO).condition_met());
+        let w = !Waits::settled_for(constant(true), Duration::ZERO);
+        assert!(!w.condition_met());
+        let exists = || Wait::new_file_exists("foo.txt");
+        let w = Waits::settled_for(exists(), Duration::from_secs(1));
+        assert!(w == Waits::settled_for(exists(), Duration::from_secs(1)));
+        assert!(w != Waits::settled_for(exists(), Duration::from_secs(2)));
+        assert!(w != Waits::stable(exists(), 1));
+    }
+
+    #[test]
+    fn leaves() {
+        let exists = |path: &str| Wait::new_file_exists(path);
+        let mut w = Waits::stable(exists("a") & (exists("b") | exists("c")), 2)
+            ^ Waits::n_of(1, vec![exists("d"), exists("e")]);
+        let paths: Vec<String> = w.leaves().map(|w| w.to_string()).collect();
+        assert_eq!(
+            paths,
+            [
+                r#"exists("a")"#,
+                r#"exists("b")"#,
+                r#"exists("c")"#,
+                r#"exists("d")"#,
+                r#"exists("e")"#
+            ]
+        );
+
+        for leaf in w.leaves_mut() {
+            *leaf = Wait::new_always();
         }
+        assert!(w.leaves().all(|w| w.to_string() == "always()"));
+        assert_eq!(Waits::from(exists("a")).leaves().count(), 1);
+    }
+
+    #[test]
+    fn memoized() {
+        let checks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let c = checks.clone();
+        let shared = Wait::new_custom_closure(move || {
+            c.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+
+        let w = (shared.clone() & constant(false)) | (shared.clone() & constant(true));
+        assert!(w.condition_met());
+        assert_eq!(checks.load(Ordering::SeqCst), 2);
+
+        assert!(w.condition_met_memoized());
+        assert_eq!(checks.load(Ordering::SeqCst), 3);
+
+        // Memoizing doesn't check anything that short-circuiting wouldn't
+        let w = constant(false) & shared;
+        assert!(!w.condition_met_memoized());
+        assert_eq!(checks.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn short_circuit_skips_expensive_checks() {
+        let local = Arc::new(AtomicBool::new(false));
+        let checks = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (l, c) = (local.clone(), checks.clone());
+        let expensive = Wait::new_custom_closure(move || {
+            c.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+        let w = Wait::new_custom_closure(move || l.load(Ordering::SeqCst)) & expensive;
+
+        // Never polled while the cheap side is unmet, however many checks
+        assert!(!w.wait_max_attempts(Duration::from_millis(1), 5));
+        assert_eq!(checks.load(Ordering::SeqCst), 0);
+
+        local.store(true, Ordering::SeqCst);
+        assert!(w.condition_met());
+        assert_eq!(checks.load(Ordering::SeqCst), 1);
+
+        let w = Waits::n_of(1, [constant(true), constant(false), constant(false)]) | constant(true);
+        assert!(w.condition_met());
+    }
+    #[test]
+    fn with_timeout() {
+        let w = Waits::from(constant(false)).with_timeout(Duration::from_millis(20));
+        assert!(!w.condition_met());
+        assert!(!w.wait_or_timeout(Duration::from_millis(5)));
+
+        let w = (constant(false) | constant(true)).with_timeout(Duration::ZERO);
+        assert!(w.wait_or_timeout(Duration::from_millis(5)));
+        assert_eq!(
+            Waits::from(Wait::new_always())
+                .with_timeout(Duration::from_secs(1))
+                .leaves()
+                .count(),
+            2
+        );
+
+        // Anything else is only ever met
+        assert!(Waits::from(constant(true)).wait_or_timeout(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn describe() {
+        use crate::WaitsStatus;
+
+        let leaf = |label: &str, met| WaitsStatus {
+            label: label.to_string(),
+            met,
+            children: Vec::new(),
+        };
+
+        let missing = Wait::new_file_exists("/nonexistent/waitforit");
+        let w =
+            (Wait::new_always() | missing.clone()) & Waits::n_of(1, [!missing.clone(), missing]);
+        let status = w.describe();
+        assert_eq!(
+            status,
+            WaitsStatus {
+                label: "AND".to_string(),
+                met: Some(true),
+                children: vec![
+                    WaitsStatus {
+                        label: "OR".to_string(),
+                        met: Some(true),
+                        children: vec![
+                            leaf("always()", Some(true)),
+                            leaf(r#"exists("/nonexistent/waitforit")"#, None),
+                        ],
+                    },
+                    WaitsStatus {
+                        label: "AT LEAST 1 OF".to_string(),
+                        met: Some(true),
+                        children: vec![
+                            leaf(r#"NOT exists("/nonexistent/waitforit")"#, Some(true)),
+                            leaf(r#"exists("/nonexistent/waitforit")"#, None),
+                        ],
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            status.to_string(),
+            [
+                "AND: met",
+                "  OR: met",
+                "    always(): met",
+                r#"    exists("/nonexistent/waitforit"): skipped"#,
+                "  AT LEAST 1 OF: met",
+                r#"    NOT exists("/nonexistent/waitforit"): met"#,
+                r#"    exists("/nonexistent/waitforit"): skipped"#,
+            ]
+            .join("\n")
+        );
+
+        // Without short-circuiting, everything is checked
+        let status = w.describe_all();
+        assert!(status.is_met());
+        assert_eq!(status.children[0].children[1].met, Some(false));
+        assert_eq!(status.children[1].children[1].met, Some(false));
+
+        let w = Waits::stable(Wait::new_always(), 2);
+        assert!(!w.describe().is_met());
+        let status = w.describe();
+        assert!(status.is_met());
+        assert_eq!(status.label, "STABLE FOR 2 CHECKS");
     }
 }